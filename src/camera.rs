@@ -0,0 +1,206 @@
+use std::time::Duration;
+
+use render::View;
+use units::dt2ms_f32;
+use units::linear::V2;
+
+/// Size of the window (in pixels) until the window system reports otherwise.
+pub static DEFAULT_VIEWPORT: (u32, u32) = (1280, 720);
+
+// zoom is clamped to this range, 2.0 shows half as much of the world
+static MIN_ZOOM: f32 = 0.25;
+static MAX_ZOOM: f32 = 4.0;
+
+// time (in ms) for the camera to cover ~63% of the distance to its target
+static FOLLOW_LAG_MS: f32 = 120.0;
+
+/// The camera decides which part of the world is on screen.
+///
+/// World coordinates are square: one unit is the height of the window
+/// at a zoom of 1.0, and y grows upward. How much of the world is visible
+/// horizontally depends on the window's aspect ratio, so a square in the
+/// world stays square regardless of the window's size.
+///
+/// Window coordinates are measured in pixels from the top-left corner w/
+/// y growing downward, this is what the window system reports for the
+/// mouse cursor.
+///
+/// The camera is updated once per simulation step (so it replays like any
+/// other part of the simulation) and is blended between steps for drawing.
+pub struct Camera {
+    position: V2,
+    zoom:     f32,
+    rotation: f32,
+    viewport: (u32, u32),
+
+    // state as of the previous simulation step (for interpolation)
+    prev_position: V2,
+    prev_zoom:     f32,
+    prev_rotation: f32,
+
+    target:     V2,
+    follow_lag: f32,
+}
+
+impl Camera {
+    pub fn new(position: V2) -> Self {
+        Camera {
+            position: position,
+            zoom:     1.0,
+            rotation: 0.0,
+            viewport: DEFAULT_VIEWPORT,
+
+            prev_position: position,
+            prev_zoom:     1.0,
+            prev_rotation: 0.0,
+
+            target:     position,
+            follow_lag: FOLLOW_LAG_MS,
+        }
+    }
+
+    /// Sets the point the camera drifts toward, see `update()`.
+    pub fn follow(&mut self, target: V2) { self.target = target; }
+
+    /// Sets the size of the window (in pixels) the camera projects onto.
+    pub fn set_viewport(&mut self, viewport: (u32, u32)) {
+        self.viewport = (viewport.0.max(1), viewport.1.max(1));
+    }
+
+    pub fn set_zoom(&mut self, zoom: f32) { self.zoom = zoom.max(MIN_ZOOM).min(MAX_ZOOM); }
+
+    /// Multiplies the current zoom by `factor`.
+    pub fn zoom_by(&mut self, factor: f32) {
+        let zoom = self.zoom * factor;
+        self.set_zoom(zoom);
+    }
+
+    /// Sets the camera's rotation (counter-clockwise, in radians.)
+    #[allow(dead_code)]
+    pub fn set_rotation(&mut self, rotation: f32) { self.rotation = rotation; }
+
+    pub fn position(&self) -> V2 { self.position }
+
+    pub fn zoom(&self) -> f32 { self.zoom }
+
+    /// Advances the camera by a single simulation step.
+    pub fn update(&mut self, dt: Duration) {
+        let dt = dt2ms_f32(dt);
+        self.prev_position = self.position;
+        self.prev_zoom     = self.zoom;
+        self.prev_rotation = self.rotation;
+
+        // ease toward the target, independent of the step size
+        let t = if self.follow_lag > 0.0 { 1.0 - (-dt / self.follow_lag).exp() } else { 1.0 };
+        self.position = self.position.lerp(self.target, t);
+    }
+
+    /// Width of the window divided by its height.
+    pub fn aspect(&self) -> f32 { self.viewport.0 as f32 / self.viewport.1 as f32 }
+
+    /// Corners (bottom-left & top-right) of a box containing everything the
+    /// camera can currently see.
+    pub fn visible_bounds(&self) -> (V2, V2) {
+        let half_w = (self.aspect() / self.zoom.min(self.prev_zoom)) / 2.0;
+        let half_h = (1.0 / self.zoom.min(self.prev_zoom)) / 2.0;
+
+        // a rotated view can see as far as its corners in any direction
+        let reach = if self.rotation == 0.0 { V2::at(half_w, half_h) } else {
+            let radius = V2::at(half_w, half_h).len();
+            V2::at(radius, radius)
+        };
+
+        (self.position - reach, self.position + reach)
+    }
+
+    /// The view & projection for drawing the world onto a window of `window`
+    /// pixels, blended `alpha` of the way from the previous simulation step
+    /// to the current one. (This needn't be the viewport the simulation
+    /// sees, e.g: a replay keeps the viewport it was recorded w/.)
+    pub fn view(&self, alpha: f32, window: (u32, u32)) -> View {
+        let position = self.prev_position.lerp(self.position, alpha);
+        let zoom     = self.prev_zoom + ((self.zoom - self.prev_zoom) * alpha);
+        let rotation = self.prev_rotation + ((self.rotation - self.prev_rotation) * alpha);
+
+        project(position, zoom, rotation, window.0.max(1) as f32 / window.1.max(1) as f32)
+    }
+
+    /// Unprojects a point in window coordinates to world coordinates.
+    #[allow(dead_code)]
+    pub fn screen_to_world(&self, (x, y): (i32, i32)) -> V2 {
+        let (w, h) = self.viewport;
+        let nx = ((x as f32 / w as f32) * 2.0) - 1.0;
+        let ny = 1.0 - ((y as f32 / h as f32) * 2.0);
+
+        let local = V2::at(nx * self.aspect() / (2.0 * self.zoom), ny / (2.0 * self.zoom));
+        self.position + local.rot(self.rotation)
+    }
+
+    /// Projects a point in world coordinates to window coordinates.
+    /// (Points which are off screen land outside the window's bounds.)
+    #[allow(dead_code)]
+    pub fn world_to_screen(&self, pos: V2) -> (f32, f32) {
+        let (w, h) = self.viewport;
+        let (nx, ny) = project(self.position, self.zoom, self.rotation, self.aspect()).apply(pos.x, pos.y);
+
+        ((nx + 1.0) / 2.0 * w as f32, (1.0 - ny) / 2.0 * h as f32)
+    }
+}
+
+// builds the view which puts `position` at the center of the screen, w/
+// `1 / zoom` world units visible from the bottom of the screen to the top.
+fn project(position: V2, zoom: f32, rotation: f32, aspect: f32) -> View {
+    let (sin, cos) = (-rotation).sin_cos();
+    let (sx, sy) = (2.0 * zoom / aspect, 2.0 * zoom);
+
+    // scale * rotate(-rotation) * (p - position)
+    let matrix = [[sx * cos, sx * -sin],
+                  [sy * sin, sy *  cos]];
+
+    let offset = [-((matrix[0][0] * position.x) + (matrix[0][1] * position.y)),
+                  -((matrix[1][0] * position.x) + (matrix[1][1] * position.y))];
+
+    View { matrix: matrix, offset: offset }
+}
+
+#[cfg(test)]
+mod tests {
+    use units::linear::V2;
+    use super::Camera;
+
+    fn close(a: f32, b: f32) -> bool { (a - b).abs() < 1e-4 }
+
+    #[test]
+    fn window_center_is_camera_position() {
+        let camera = Camera::new(V2::at(3.0, -2.0));
+        let center = camera.screen_to_world((640, 360));
+        assert!(close(center.x, 3.0) && close(center.y, -2.0));
+    }
+
+    #[test]
+    fn cursor_keeps_square_proportions() {
+        let mut camera = Camera::new(V2::at(0.0, 0.0));
+        camera.set_viewport((1600, 400));
+
+        // a 100px square on screen covers as much of the world each way
+        let a = camera.screen_to_world((800, 200));
+        let b = camera.screen_to_world((900, 100));
+        assert!(close(b.x - a.x, 0.25) && close(b.y - a.y, 0.25));
+
+        // the window's right edge is `aspect / 2` from the center
+        assert!(close(camera.screen_to_world((1600, 200)).x, 2.0));
+    }
+
+    #[test]
+    fn window_to_world_round_trips() {
+        let mut camera = Camera::new(V2::at(0.5, 0.5));
+        camera.set_viewport((1920, 1080));
+        camera.set_zoom(2.0);
+        camera.set_rotation(0.3);
+
+        for &(x, y) in &[(0, 0), (1920, 1080), (123, 987), (960, 540)] {
+            let (sx, sy) = camera.world_to_screen(camera.screen_to_world((x, y)));
+            assert!((sx - x as f32).abs() < 0.01 && (sy - y as f32).abs() < 0.01);
+        }
+    }
+}
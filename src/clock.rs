@@ -0,0 +1,109 @@
+use std::time::Duration;
+
+use units::dt2ms_f32;
+
+/// Number of simulation steps per second of game time.
+pub static SIM_RATE_HZ:  u32 = 120;
+
+/// Longest frame the runloop will try to catch up on.
+pub static MAX_FRAME_MS: u64 = 250;
+
+/// The length of one step when simulating `hz` times per second.
+pub fn step_for_hz(hz: u32) -> Duration {
+    Duration::new(0, 1_000_000_000 / hz)
+}
+
+/// FixedStep decouples the simulation rate from the rendering rate.
+///
+/// Each frame the runloop feeds the wall-clock time elapsed since the
+/// previous frame into the accumulator. The simulation is then stepped
+/// in constant increments of `step` until the accumulator has been drained
+/// below a single step. Whatever remains is a fraction of a step which the
+/// renderer uses to blend between the previous & current entity states.
+///
+/// If a frame takes longer than `max_frame` the excess time is discarded,
+/// otherwise a slow frame would schedule more steps, which makes the next
+/// frame slower still. (a.k.a: the spiral of death.)
+pub struct FixedStep {
+    step:        Duration,
+    max_frame:   Duration,
+    accumulator: Duration,
+}
+
+impl FixedStep {
+    pub fn new(step: Duration, max_frame: Duration) -> Self {
+        FixedStep {
+            step:        step,
+            max_frame:   max_frame,
+            accumulator: Duration::new(0, 0),
+        }
+    }
+
+    /// The length of a single simulation step.
+    pub fn step(&self) -> Duration { self.step }
+
+    /// Adds the real time elapsed since the last frame to the accumulator.
+    /// Responds true if the frame was clamped to `max_frame`.
+    pub fn accumulate(&mut self, frame_dt: Duration) -> bool {
+        let clamped = frame_dt > self.max_frame;
+        self.accumulator += if clamped { self.max_frame } else { frame_dt };
+        clamped
+    }
+
+    /// Consumes a single step from the accumulator if one is available.
+    /// Responds false once the accumulator holds less than one step.
+    pub fn tick(&mut self) -> bool {
+        if self.accumulator < self.step { return false }
+        self.accumulator -= self.step;
+        true
+    }
+
+    /// How far (0.0 .. 1.0) the real clock has advanced into the next step.
+    pub fn alpha(&self) -> f32 {
+        dt2ms_f32(self.accumulator) / dt2ms_f32(self.step)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use super::FixedStep;
+
+    fn ms(n: u64) -> Duration { Duration::from_millis(n) }
+
+    fn drain(clock: &mut FixedStep) -> u32 {
+        let mut steps = 0;
+        while clock.tick() { steps += 1 }
+        steps
+    }
+
+    #[test]
+    fn steps_once_per_whole_step_accumulated() {
+        let mut clock = FixedStep::new(ms(10), ms(250));
+
+        assert!(!clock.accumulate(ms(35)));
+        assert_eq!(drain(&mut clock), 3);
+        assert!((clock.alpha() - 0.5).abs() < 1e-4);
+    }
+
+    #[test]
+    fn carries_partial_steps_into_the_next_frame() {
+        let mut clock = FixedStep::new(ms(10), ms(250));
+
+        clock.accumulate(ms(6));
+        assert_eq!(drain(&mut clock), 0);
+
+        clock.accumulate(ms(6));
+        assert_eq!(drain(&mut clock), 1);
+        assert!((clock.alpha() - 0.2).abs() < 1e-4);
+    }
+
+    #[test]
+    fn clamps_long_frames() {
+        let mut clock = FixedStep::new(ms(10), ms(250));
+
+        assert!(clock.accumulate(ms(1000)));
+        assert_eq!(drain(&mut clock), 25);
+        assert_eq!(clock.alpha(), 0.0);
+    }
+}
@@ -0,0 +1,109 @@
+use std::collections::VecDeque;
+
+use input::{Input, Key};
+use render::{RenderJob, Text};
+
+// size of the console's text, & its distance from the edges of the window (in pixels)
+static CONSOLE_TEXT_SIZE: f32 = 16.0;
+static CONSOLE_MARGIN:    f32 = 8.0;
+static CONSOLE_COLOR:  [f32; 4] = [1.0, 0.9, 0.5, 1.0];
+static HISTORY_COLOR:  [f32; 4] = [1.0, 0.9, 0.5, 0.6];
+
+// number of submitted lines kept around to be shown above the prompt
+static HISTORY_LINES: usize = 8;
+
+/// A debug console, toggled w/ the grave key (escape also closes it.)
+/// While it is open keyboard bindings are suppressed, and each line
+/// entered is added to a short scrollback shown above the prompt.
+pub struct Console {
+    history: VecDeque<String>,
+}
+
+impl Console {
+    pub fn new() -> Self {
+        Console { history: VecDeque::with_capacity(HISTORY_LINES) }
+    }
+
+    /// Opens or closes the console & collects any submitted lines.
+    ///
+    /// This runs once per simulation step, before the step itself, so that
+    /// replays see the console open & close at the same step they did live.
+    pub fn update(&mut self, controller: &mut Input) {
+        let toggled = controller.was_key_pressed(Key::Grave);
+        let closed  = controller.was_key_pressed(Key::Escape) && controller.is_text_input();
+
+        if closed || (toggled && controller.is_text_input()) {
+            controller.end_text_input();
+        } else if toggled {
+            controller.begin_text_input();
+        }
+
+        while let Some(line) = controller.take_text_line() {
+            if self.history.len() == HISTORY_LINES { self.history.pop_front(); }
+            self.history.push_back(line);
+        }
+    }
+
+    /// Shows the line being typed along the bottom of a `window` sized
+    /// window, w/ the scrollback above it, if the console is open.
+    pub fn draw(&self, controller: &Input, window: (u32, u32), jobs: &mut Vec<RenderJob>) {
+        if !controller.is_text_input() { return }
+
+        let (w, h) = window;
+        let width  = w as f32 - (CONSOLE_MARGIN * 2.0);
+        let line_h = CONSOLE_TEXT_SIZE * 1.5;
+        let prompt = h as f32 - CONSOLE_MARGIN - line_h;
+
+        for (idx, line) in self.history.iter().rev().enumerate() {
+            let y = prompt - (line_h * (idx + 1) as f32);
+            if y < 0.0 { break }
+
+            let text = Text::from(&format!("> {}", line), CONSOLE_MARGIN, y)
+                            .size(CONSOLE_TEXT_SIZE)
+                            .color(HISTORY_COLOR);
+
+            jobs.push(RenderJob::DrawText(text));
+        }
+
+        let line = format!("> {}_", controller.text_line());
+        let text = Text::from(&line, CONSOLE_MARGIN, prompt)
+                        .size(CONSOLE_TEXT_SIZE)
+                        .color(CONSOLE_COLOR)
+                        .wrap(width);
+
+        jobs.push(RenderJob::DrawText(text));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use input::{Input, InputEvent, Key};
+    use super::{Console, HISTORY_LINES};
+
+    fn press(controller: &mut Input, key: Key) {
+        controller.begin_new_frame();
+        controller.apply(InputEvent::KeyDown(key));
+        controller.apply(InputEvent::KeyUp(key));
+    }
+
+    #[test]
+    fn keeps_recent_lines() {
+        let (mut console, mut controller) = (Console::new(), Input::new());
+
+        press(&mut controller, Key::Grave);
+        console.update(&mut controller);
+        assert!(controller.is_text_input());
+
+        for n in 0..HISTORY_LINES + 2 {
+            for ch in format!("line {}\n", n).chars() { controller.apply(InputEvent::Char(ch)) }
+        }
+
+        console.update(&mut controller);
+        assert_eq!(console.history.len(), HISTORY_LINES);
+        assert_eq!(console.history.front().map(String::as_str), Some("line 2"));
+
+        press(&mut controller, Key::Escape);
+        console.update(&mut controller);
+        assert!(!controller.is_text_input());
+    }
+}
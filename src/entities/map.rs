@@ -1,95 +1,103 @@
-use std::cell::RefCell;
-use std::rc::Rc;
+use std::sync::Arc;
 
 use rand::{Rng, SeedableRng, XorShiftRng};
 
-use render::{Rect, RenderGroup, RenderJob};
-use units::drawing::RGBA;
+use render::{ColorBatch, Rect, RenderJob, Tint};
 use units::linear::V2;
 
-static STAR_BG:   RGBA     = (  0,   0,   0, 255);
-static STAR_FG:   RGBA     = (255, 255, 255, 255);
-static STAR_SEED: [u32; 4] = [157, 27, 24, 133];
+static STAR_COLOR: Tint     = [1.0, 1.0, 1.0, 1.0];
+static STAR_SIZE:  f32      = 1.0 / 720.0;
+/// Seed used to generate the world unless one is given on startup.
+pub static STAR_SEED: [u32; 4] = [157, 27, 24, 133];
 
 pub struct World {
-    player_pos: V2,
-
-    tx_star_bg: usize,
-    tx_star_fg: usize,
-
+    seed:      [u32; 4],
     entropy:   XorShiftRng,
-    starfield: Rc<RefCell<Vec<Rect>>>,
+    starfield: Arc<Vec<Rect>>,
 }
 
 impl World {
-    pub fn new(display: &mut RenderGroup, camera_center: V2) -> World {
-        let fg_bitmap  = vec![vec![STAR_FG; 1]; 1];
-        let bg_bitmap  = vec![vec![STAR_BG; 1]; 1];
-        let star_fg_id = display.store_texture(fg_bitmap);
-        let star_bg_id = display.store_texture(bg_bitmap);
-
+    pub fn new(seed: [u32; 4]) -> World {
         World {
-            player_pos: camera_center,
-
-            tx_star_bg: star_bg_id,
-            tx_star_fg: star_fg_id,
-
             // TODO: magic number for capacity
-            // center + cardinal directions + diagonals = 9 screens
-            // 50 stars per screen, 1 rect per star...
-            entropy:   XorShiftRng::from_seed(STAR_SEED),
-            starfield: Rc::new(RefCell::new(Vec::with_capacity(3 * 3 * 50))),
+            // center + cardinal directions + diagonals = 9 tiles
+            // 50 stars per tile, 1 rect per star...
+            // (zooming out will need more tiles than this.)
+            seed:      seed,
+            entropy:   XorShiftRng::from_seed(seed),
+            starfield: Arc::new(Vec::with_capacity(3 * 3 * 50)),
         }
     }
 
-    pub fn update(&mut self, pos: V2) {
-        let mut starfield = self.starfield.borrow_mut();
-        self.player_pos = pos;
+    /// Fills in the stars for every tile which overlaps the box from
+    /// `min` to `max`. (see `Camera::visible_bounds()`)
+    pub fn update(&mut self, (min, max): (V2, V2)) {
+        let starfield = Arc::make_mut(&mut self.starfield);
         starfield.clear();
 
-        // convert player pos to tile space
-        let ox = self.player_pos.x - 0.5;
-        let oy = self.player_pos.y - 0.5;
+        // figure out visible tile boundaries
+        let left  = min.x.floor() as i32;
+        let bot   = min.y.floor() as i32;
+        let right = max.x.ceil()  as i32;
+        let top   = max.y.ceil()  as i32;
 
-        // figure out visible screen boundaries
-        let left  = (ox - 1.0).floor() as i32;
-        let bot   = (oy - 1.0).floor() as i32;
-        let right = (ox + 1.0).ceil()  as i32;
-        let top   = (oy + 1.0).ceil()  as i32;
-
-        for y in bot..top { // -1 => 1
-            for x in left..right { // -1 => 1
-                self.entropy.reseed([x as u32, y as u32, 0xDEADBEEF, 0xCAFEBABE]);
+        for y in bot..top {
+            for x in left..right {
+                self.entropy.reseed(tile_seed(self.seed, x, y));
 
                 for _star in 0..50 {
                     // generate tile relative coord for star
+                    // (tiles are 1x1 world units, stars are a pixel at 720p)
                     let px = self.entropy.gen_range(0,1280);
                     let py = self.entropy.gen_range(0,720);
                     let rel_x = (px as f32) / 1280.0;
                     let rel_y = (py as f32) /  720.0;
 
-                    // generate tile absolute coord in screen space
+                    // generate tile absolute coord in world space
                     let abs_x = (x as f32) + rel_x;
                     let abs_y = (y as f32) + rel_y;
 
-                    starfield.push(Rect {x: abs_x, y: abs_y, z: -0.6, w:  1.0 / 1280.0, h: 1.0 / 720.0});
+                    starfield.push(Rect {x: abs_x, y: abs_y, z: -0.6, w: STAR_SIZE, h: STAR_SIZE});
                 }
             }
         }
 
     }
-    
+
+    pub fn star_count(&self) -> usize { self.starfield.len() }
+
     pub fn draw(&self, jobs: &mut Vec<RenderJob>) {
-        let x1 = (self.player_pos.x * 2.0) - 1.0;
-        let y1 = (self.player_pos.y * 2.0) - 1.0;
-
-        // TODO: there is something fucky about coordinates
-        // why are these inverted? why is abs_y in the integration inverted?
-        // nobody knows, but it doesn't work any other way
-        if !self.starfield.borrow().is_empty() {
-            jobs.push(RenderJob::UniformTranslate([-x1, -y1]));
-            jobs.push(RenderJob::DrawMany(self.tx_star_fg, self.starfield.clone()));
-            jobs.push(RenderJob::ResetUniforms);
+        if !self.starfield.is_empty() {
+            jobs.push(RenderJob::FillMany(ColorBatch::from(STAR_COLOR, self.starfield.clone())));
         }
     }
 }
+
+// seeds the stars of tile (x, y). the world's seed is folded in relative to
+// `STAR_SEED`, so the default world keeps the tiles it has always had.
+fn tile_seed(seed: [u32; 4], x: i32, y: i32) -> [u32; 4] {
+    let tile = [(x as u32) ^ seed[0] ^ STAR_SEED[0],
+                (y as u32) ^ seed[1] ^ STAR_SEED[1],
+                0xDEADBEEF ^ seed[2] ^ STAR_SEED[2],
+                0xCAFEBABE ^ seed[3] ^ STAR_SEED[3]];
+
+    // xorshift rejects the all zero seed, which some world seed could hit
+    if tile == [0; 4] { [0, 0, 0, 1] } else { tile }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{tile_seed, STAR_SEED};
+
+    #[test]
+    fn default_seed_keeps_the_original_tiles() {
+        assert_eq!(tile_seed(STAR_SEED, 3, -2), [3, -2i32 as u32, 0xDEADBEEF, 0xCAFEBABE]);
+    }
+
+    #[test]
+    fn tiles_never_get_the_zero_seed() {
+        let seed = [STAR_SEED[0], STAR_SEED[1], STAR_SEED[2] ^ 0xDEADBEEF, STAR_SEED[3] ^ 0xCAFEBABE];
+        assert!(tile_seed(seed, 0, 0) != [0; 4]);
+        assert!(tile_seed(seed, 1, 0) != tile_seed(seed, 0, 0));
+    }
+}
@@ -1,9 +1,7 @@
 use std::time::Duration;
 
-use glium::glutin::VirtualKeyCode as VKC;
-
 use units::{dt2ms, Direction};
-use input::Input;
+use input::{Input, Key};
 use render::{self, RenderJob};
 
 static SCROLL_V: f32 = 0.001; // (.001px * 1000ms) = 1 texture height / sec.
@@ -18,10 +16,10 @@ impl ScrollyBox {
 
     pub fn update(&mut self, controller: &Input, dt: Duration) {
 
-        if controller.is_key_held(VKC::Up)    { self.integrate(dt, Direction::Up)    }
-        if controller.is_key_held(VKC::Right) { self.integrate(dt, Direction::Right) }
-        if controller.is_key_held(VKC::Down)  { self.integrate(dt, Direction::Down)  }
-        if controller.is_key_held(VKC::Left)  { self.integrate(dt, Direction::Left)  }
+        if controller.is_key_held(Key::Up)    { self.integrate(dt, Direction::Up)    }
+        if controller.is_key_held(Key::Right) { self.integrate(dt, Direction::Right) }
+        if controller.is_key_held(Key::Down)  { self.integrate(dt, Direction::Down)  }
+        if controller.is_key_held(Key::Left)  { self.integrate(dt, Direction::Left)  }
     }
 
     fn integrate(&mut self, dt: Duration, dir: Direction) {
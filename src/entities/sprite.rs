@@ -1,23 +1,33 @@
 use std::cell::RefCell;
 use std::f32::consts as r32;
 use std::rc::Rc;
+use std::sync::Arc;
 use std::time::Duration;
 
-use glium::glutin::VirtualKeyCode as VKC;
-
-use input::Input;
-use render::{self, Rect, TexRect, RenderJob, RenderGroup};
-use units::{dt2ms, Direction};
+use input::{Action, Input};
+use render::{self, Animation, AnimationPlayer, Playback, Rect, RenderJob};
+use render::{SpriteSheet, TexBatch, TexRect, TextureHandle, TextureStore, Tint, Transform};
+use render::shaders;
+use units::{dt2ms_f32, Direction};
 use units::linear::V2;
 
-// TODO: how to factor aspect out of here...
-static SHIP_ACCEL:  f32  = (128.0 / 1280.0) * 0.001 * 0.001; // px/s^2
-static SHIP_VMAX:   f32  = (512.0 / 1280.0) * 0.001;         // px/s
-static SHIP_ROT:    f32  = r32::PI * 0.001;                  // rad/s
-static BULLET_VMAX: f32  = 0.0007;
+// distances are in world units (see `Camera`), times are in ms
+static SHIP_SIZE:    f32  = 0.035;
+static SHIP_ACCEL:   f32  = 0.1 * 0.001 * 0.001; // u/ms^2
+static SHIP_VMAX:    f32  = 0.4 * 0.001;         // u/ms
+static SHIP_ROT:     f32  = r32::PI * 0.001;     // rad/ms
+static BULLET_VMAX:  f32  = 0.0007;              // u/ms
+static BULLET_FADE:  f32  = 0.1;                 // u from the edge of the screen
+
+// flames flicker back & forth through a row of `FLAME_FRAMES` frames
+static FLAMES_SHEET: &'static str = "assets/sprites/loader/flames.sheet";
+static FLAME_FRAMES:   usize = 4;
+static FLAME_ROWS:     u32   = 5;
+static FLAME_FRAME_MS: f32   = 50.0;
 
 pub struct Particle {
     pos: V2, vel: V2,
+    prev_pos: V2,
     pub is_alive: bool,
 }
 
@@ -27,6 +37,7 @@ impl Particle {
         Particle {
             pos: V2::at(x, y),
             vel: V2::at(vx, vy),
+            prev_pos: V2::at(x, y),
 
             is_alive: true,
         }
@@ -37,105 +48,164 @@ pub struct Sprite {
     pos: V2, vel: V2,
     rotation: f32,
 
+    // state as of the previous simulation step (for interpolation)
+    prev_pos: V2,
+    prev_rotation: f32,
+
     particles: Vec<Particle>,
-    particle_drawbuf: Rc<RefCell<Vec<Rect>>>,
+    particle_drawbuf: RefCell<Arc<Vec<Rect>>>,
+    particle_colors:  RefCell<Arc<Vec<Tint>>>,
 
-    rev_ap_engaged: bool,
     rev_ap_active:  bool,
     rev_ap_heading: V2,
 
-    tx_crate: usize,
-    tx_idle:  usize,
-    tx_fly_w: usize,
-    tx_fly_a: usize,
-    // tx_fly_s: usize, // TODO: ships may optionally have reverse thrusters?
-    tx_fly_d: usize,
+    tx_crate: TextureHandle,
+    tx_idle:  TextureHandle,
+
+    anim_fly_w: Rc<Animation>,
+    anim_fly_a: Rc<Animation>,
+    // anim_fly_s: Rc<Animation>, // TODO: ships may optionally have reverse thrusters?
+    anim_fly_d: Rc<Animation>,
 
-    tx_fly_q: usize,
-    tx_fly_e: usize,
+    anim_fly_q: Rc<Animation>,
+    anim_fly_e: Rc<Animation>,
 
-    engine_tex: Option<usize>,
-    thrust_tex: Option<usize>,
+    // flames are only drawn while their engine is firing
+    engine_anim: AnimationPlayer,
+    thrust_anim: AnimationPlayer,
+    engine_on:   bool,
+    thrust_on:   bool,
+
+    // the part of the world on screen, bullets leaving it are removed
+    bounds: (V2, V2),
 }
 
 impl Sprite {
-    pub fn new(display: &mut RenderGroup) -> Self {
+    pub fn new<T: TextureStore>(display: &mut T) -> Self {
+        let flames = Rc::new(SpriteSheet::load(display, FLAMES_SHEET).unwrap_or_else(|err| {
+            println!("could not load flames, using fallback: {}", err);
+            SpriteSheet::grid(display.fallback(), FLAME_FRAMES as u32, FLAME_ROWS)
+        }));
+
+        let (fly_w, fly_a, fly_d, fly_q, fly_e) = (flame(0), flame(1), flame(2), flame(3), flame(4));
+
         Sprite {
             pos: V2::at(0.5, 0.5),
             vel: V2::at(0.0, 0.0),
             rotation: (r32::PI) / 2.0,
 
+            prev_pos: V2::at(0.5, 0.5),
+            prev_rotation: (r32::PI) / 2.0,
+
             // misc storage.
             particles: Vec::with_capacity(render::MAX_PARTICLES),
-            particle_drawbuf: Rc::new(RefCell::new(Vec::with_capacity(render::MAX_PARTICLES))),
+            particle_drawbuf: RefCell::new(Arc::new(Vec::with_capacity(render::MAX_PARTICLES))),
+            particle_colors:  RefCell::new(Arc::new(Vec::with_capacity(render::MAX_PARTICLES))),
 
-            rev_ap_engaged: false,
             rev_ap_active:  false,
             rev_ap_heading: V2::at(0.0, 1.0),
 
             // TODO: asset management system would be nice...
             // texture storage
-            tx_crate: display.load_tga("assets/sprites/loader/8xcrate.tga"),
-            tx_idle:  display.load_tga("assets/sprites/loader/loadertex.tga"),
-            tx_fly_w: display.load_tga("assets/sprites/loader/loaderw.tga"),
-            tx_fly_a: display.load_tga("assets/sprites/loader/loadera.tga"),
-            // tx_fly_s: display.load_tga("assets/sprites/loader/loaders.tga"),
-            tx_fly_d: display.load_tga("assets/sprites/loader/loaderd.tga"),
-            tx_fly_q: display.load_tga("assets/sprites/loader/loaderq.tga"),
-            tx_fly_e: display.load_tga("assets/sprites/loader/loadere.tga"),
-
-            // tx_idle:  display.load_tga("assets/sprites/ship/SHIPB001.tga"),
-            // tx_fly_w: display.load_tga("assets/sprites/ship/SHIPW001.tga"),
-            // tx_fly_a: display.load_tga("assets/sprites/ship/SHIPA001.tga"),
-            // tx_fly_s: display.load_tga("assets/sprites/ship/SHIPS001.tga"),
-            // tx_fly_d: display.load_tga("assets/sprites/ship/SHIPD001.tga"),
-            // tx_fly_q: display.load_tga("assets/sprites/ship/SHIPQ001.tga"),
-            // tx_fly_e: display.load_tga("assets/sprites/ship/SHIPE001.tga"),
-
-            // renderer flags
-            engine_tex: None,
-            thrust_tex: None,
+            tx_crate: display.load_image_async("assets/sprites/loader/8xcrate.tga"),
+            tx_idle:  display.load_image_async("assets/sprites/loader/loadertex.tga"),
+
+            // tx_idle:  display.load_image("assets/sprites/ship/SHIPB001.tga"),
+            // tx_fly_w: display.load_image("assets/sprites/ship/SHIPW001.tga"),
+            // tx_fly_a: display.load_image("assets/sprites/ship/SHIPA001.tga"),
+            // tx_fly_s: display.load_image("assets/sprites/ship/SHIPS001.tga"),
+            // tx_fly_d: display.load_image("assets/sprites/ship/SHIPD001.tga"),
+            // tx_fly_q: display.load_image("assets/sprites/ship/SHIPQ001.tga"),
+            // tx_fly_e: display.load_image("assets/sprites/ship/SHIPE001.tga"),
+
+            // animations
+            engine_anim: AnimationPlayer::new(flames.clone(), fly_w.clone()),
+            thrust_anim: AnimationPlayer::new(flames, fly_q.clone()),
+            engine_on:   false,
+            thrust_on:   false,
+
+            anim_fly_w: fly_w,
+            anim_fly_a: fly_a,
+            anim_fly_d: fly_d,
+            anim_fly_q: fly_q,
+            anim_fly_e: fly_e,
+
+            bounds: (V2::at(-1.0, -1.0), V2::at(1.0, 1.0)),
         }
     }
 
     pub fn update(&mut self, controller: &Input, dt: Duration) {
-        self.engine_tex = None;
-        self.thrust_tex = None;
+        self.prev_pos = self.pos;
+        self.prev_rotation = self.rotation;
+
+        self.engine_anim.update(dt);
+        self.thrust_anim.update(dt);
+        self.engine_on = false;
+        self.thrust_on = false;
         self.step_particles(dt);
-        self.pos += self.vel * dt2ms(dt) as f32;
+        self.pos += self.vel * dt2ms_f32(dt);
 
         // check if player wants us to auto-invert the heading
         // if so we set the desired heading to our current heading rotated 180deg.
-        if !controller.is_key_held(VKC::S) { self.rev_ap_engaged = false; }
-        if controller.was_key_pressed(VKC::S) && !self.rev_ap_engaged { 
-            self.rev_ap_engaged = true;
+        // (the autopilot only flies while the action is held, a fresh press is needed to re-arm it.)
+        let ap_held = controller.action_held_ticks(Action::ReverseAutopilot);
+        if ap_held == 1 {
             self.rev_ap_active  = true;
             self.rev_ap_heading = self.vel.norm().rot(r32::PI);
-
-            println!("ap {:?} vnorm", self.vel.norm());
-            println!("ap {:?} tgt hdg", self.rev_ap_heading);
         }
 
-        if self.rev_ap_active && self.rev_ap_engaged { self.autopilot_reverse(dt); return }
+        if self.rev_ap_active && ap_held > 0 { self.autopilot_reverse(dt); return }
 
-        // otherwise integrate normal movement
-             if controller.is_key_held(VKC::W) { self.engine_tex = Some(self.tx_fly_w); self.integrate(dt, Direction::Up)    }
-        else if controller.is_key_held(VKC::A) { self.engine_tex = Some(self.tx_fly_a); self.integrate(dt, Direction::Left)  }
-        else if controller.is_key_held(VKC::D) { self.engine_tex = Some(self.tx_fly_d); self.integrate(dt, Direction::Right) }
+        // otherwise integrate normal movement, scaled by how hard the
+        // player is pushing (keys always push all the way.)
+        let thrust   = controller.action_value(Action::Thrust);
+        let strafe_l = controller.action_value(Action::StrafeLeft);
+        let strafe_r = controller.action_value(Action::StrafeRight);
+        let rot_ccw  = controller.action_value(Action::RotateCCW);
+        let rot_cw   = controller.action_value(Action::RotateCW);
 
-             if controller.is_key_held(VKC::Q) { self.thrust_tex = Some(self.tx_fly_q); self.rotate(dt, Direction::Left)     }
-        else if controller.is_key_held(VKC::E) { self.thrust_tex = Some(self.tx_fly_e); self.rotate(dt, Direction::Right)    }
+             if thrust   > 0.0 { self.fire_engine(Direction::Up);    self.integrate(dt, Direction::Up,    thrust)   }
+        else if strafe_l > 0.0 { self.fire_engine(Direction::Left);  self.integrate(dt, Direction::Left,  strafe_l) }
+        else if strafe_r > 0.0 { self.fire_engine(Direction::Right); self.integrate(dt, Direction::Right, strafe_r) }
+
+             if rot_ccw  > 0.0 { self.fire_thruster(Direction::Left);  self.rotate(dt, Direction::Left,  rot_ccw) }
+        else if rot_cw   > 0.0 { self.fire_thruster(Direction::Right); self.rotate(dt, Direction::Right, rot_cw)  }
 
         // fire ze missiles
-        if controller.was_key_pressed(VKC::Space) {  self.pewpew(); }
+        if controller.was_action_pressed(Action::Fire) {  self.pewpew(); }
+    }
+
+    // shows the flame for the engine pushing the ship toward `dir`
+    fn fire_engine(&mut self, dir: Direction) {
+        let anim = match dir {
+            Direction::Left  => &self.anim_fly_a,
+            Direction::Right => &self.anim_fly_d,
+            _ => &self.anim_fly_w,
+        };
+
+        self.engine_anim.play(anim);
+        self.engine_on = true;
+    }
+
+    // shows the flame for the thrusters turning the ship toward `dir`
+    fn fire_thruster(&mut self, dir: Direction) {
+        let anim = match dir {
+            Direction::Right => &self.anim_fly_e,
+            _ => &self.anim_fly_q,
+        };
+
+        self.thrust_anim.play(anim);
+        self.thrust_on = true;
     }
 
     fn step_particles(&mut self, dt: Duration) {
+        let (min, max) = self.bounds;
         for particle in &mut self.particles {
             // apply force in direction of heading
-            particle.pos += particle.vel * dt2ms(dt) as f32;
-            let on_x = particle.pos.x > -1.0 && particle.pos.x < 1.0;
-            let on_y = particle.pos.y > -1.0 && particle.pos.y < 1.0;
+            particle.prev_pos = particle.pos;
+            particle.pos += particle.vel * dt2ms_f32(dt);
+            let on_x = particle.pos.x > min.x && particle.pos.x < max.x;
+            let on_y = particle.pos.y > min.y && particle.pos.y < max.y;
             particle.is_alive = on_x && on_y;
         }
 
@@ -145,45 +215,62 @@ impl Sprite {
     fn pewpew(&mut self) {
         if self.particles.len() == render::MAX_PARTICLES { return }
 
-        let (w,h) = (0.035, 0.035);
-        let cx = 0.5;
-        let cy = 0.5;
-
         // fire from current heading, no accel time
         let bvel = V2::at(1.0, 0.0).rot(self.rotation);
         let bvel = bvel.set_len(BULLET_VMAX);
-        self.particles.push(Particle::at_speed(cx, cy, bvel.x, bvel.y));
+        self.particles.push(Particle::at_speed(self.pos.x, self.pos.y, bvel.x, bvel.y));
     }
 
-    pub fn draw(&self, jobs: &mut Vec<RenderJob>) {
-        // TODO: normalized coords
-        let (w,h) = (0.035, 0.035);
-        let cx = 0.5 - (w / 2.0);
-        let cy = 0.5 - (h / 2.0);
+    /// Draws the sprite blended `alpha` of the way from its previous
+    /// simulation step to its current one.
+    pub fn draw(&self, jobs: &mut Vec<RenderJob>, alpha: f32) {
+        let (w,h) = (SHIP_SIZE, SHIP_SIZE);
+        let pos = self.prev_pos.lerp(self.pos, alpha);
+        let cx = pos.x - (w / 2.0);
+        let cy = pos.y - (h / 2.0);
 
         // draw our engine & thruster sprites w/ current orientation
-        jobs.push(RenderJob::UniformRotate([self.rotation - (r32::PI / 2.0), 0.0]));
-        jobs.push(RenderJob::Draw(TexRect::from(self.tx_idle, cx, cy, -0.5, w, h)));
-        if let Some(tx) = self.engine_tex { jobs.push(RenderJob::Draw(TexRect::from(tx, cx, cy, -0.54, w, h))) }
-        if let Some(tx) = self.thrust_tex { jobs.push(RenderJob::Draw(TexRect::from(tx, cx, cy, -0.55, w, h))) }
-        jobs.push(RenderJob::ResetUniforms);
+        let rotation = self.prev_rotation + ((self.rotation - self.prev_rotation) * alpha);
+        let facing = Transform::rotate_about(rotation - (r32::PI / 2.0), pos.x, pos.y);
+        jobs.push(RenderJob::Draw(TexRect::from(self.tx_idle, cx, cy, -0.5, w, h).transform(facing)));
+        if self.engine_on { jobs.push(RenderJob::Draw(TexRect::from(self.engine_anim.texture(), cx, cy, -0.54, w, h).transform(facing).shader(shaders::ADDITIVE))) }
+        if self.thrust_on { jobs.push(RenderJob::Draw(TexRect::from(self.thrust_anim.texture(), cx, cy, -0.55, w, h).transform(facing).shader(shaders::ADDITIVE))) }
        
         // draw particles 
         if self.particles.is_empty() { return; }
 
-        { // store particles in drawbuffer
-            let mut pbuf = self.particle_drawbuf.borrow_mut();
+        { // store particles in drawbuffer, fading them out as they near the edge of the screen
+            // (last frame's jobs are gone by now, so these refill in place)
+            let mut pbuf   = self.particle_drawbuf.borrow_mut();
+            let mut colors = self.particle_colors.borrow_mut();
+            let pbuf   = Arc::make_mut(&mut pbuf);
+            let colors = Arc::make_mut(&mut colors);
             pbuf.clear();
+            colors.clear();
             for p in &self.particles {
-                pbuf.push(Rect { x: p.pos.x, y: p.pos.y, z: -0.56, w: w / 2.0, h: h / 2.0 });
+                let ppos = p.prev_pos.lerp(p.pos, alpha);
+                let fade = (edge_distance(ppos, self.bounds) / BULLET_FADE).max(0.0).min(1.0);
+                pbuf.push(Rect { x: ppos.x - (w / 4.0), y: ppos.y - (h / 4.0), z: -0.56, w: w / 2.0, h: h / 2.0 });
+                colors.push([1.0, 1.0, 1.0, fade]);
             }
         }
 
-        jobs.push(RenderJob::DrawMany(self.tx_crate, self.particle_drawbuf.clone()));
+        let particles = TexBatch::from(self.tx_crate, self.particle_drawbuf.borrow().clone()).colors(self.particle_colors.borrow().clone());
+        jobs.push(RenderJob::DrawMany(particles));
     }
 
     pub fn position(&self) -> V2 { self.pos }
 
+    pub fn velocity(&self) -> V2 { self.vel }
+
+    pub fn rotation(&self) -> f32 { self.rotation }
+
+    pub fn particle_count(&self) -> usize { self.particles.len() }
+
+    /// Sets the part of the world which is on screen, as corners (bottom-left
+    /// & top-right) of a box. Bullets are removed once they leave it.
+    pub fn set_bounds(&mut self, bounds: (V2, V2)) { self.bounds = bounds; }
+
     fn autopilot_reverse(&mut self, dt: Duration) {
         let origin = V2::at(1.0, 0.0);
         let dest = self.rev_ap_heading;
@@ -191,7 +278,7 @@ impl Sprite {
 
         // rotate & compare to determine if we stop
         // we discretize this into degrees to avoid FP error
-        self.rotate(dt, Direction::Left);
+        self.rotate(dt, Direction::Left, 1.0);
 
         let rad_to_deg = 180.0 / r32::PI;
         let src_deg = origin.rot(self.rotation).theta() * rad_to_deg;
@@ -202,7 +289,9 @@ impl Sprite {
         if abs_diff <= max_diff { self.rev_ap_active = false; }
     }
 
-    fn integrate(&mut self, dt: Duration, dir: Direction) {
+    /// Accelerates the ship in `dir` relative to its heading, `magnitude`
+    /// scales the engine output from 0.0 (off) to 1.0 (full thrust.)
+    fn integrate(&mut self, dt: Duration, dir: Direction, magnitude: f32) {
         let (ax, ay) = match dir {
             Direction::Up    => ( SHIP_ACCEL, 0.0),
             Direction::Down  => (-SHIP_ACCEL, 0.0),
@@ -211,8 +300,8 @@ impl Sprite {
         };
 
         // apply force in direction of heading
-        let acc  = V2::at(ax, ay).rot(self.rotation);
-        self.vel += acc * (dt2ms(dt) as f32);
+        let acc  = V2::at(ax, ay).rot(self.rotation) * magnitude;
+        self.vel += acc * dt2ms_f32(dt);
 
         // clamp magnitude of the vector ^^,
         if self.vel.len() > SHIP_VMAX {
@@ -220,13 +309,109 @@ impl Sprite {
         }
     }
 
-    fn rotate(&mut self, dt: Duration, dir: Direction) {
+    /// Turns the ship toward `dir`, `magnitude` scales the turn rate
+    /// from 0.0 (not turning) to 1.0 (full rate.)
+    fn rotate(&mut self, dt: Duration, dir: Direction, magnitude: f32) {
         let vr = match dir {
             Direction::Left  =>  SHIP_ROT,
             Direction::Right => -SHIP_ROT,
             _ => panic!("tilemap cannot rotate this direction ..."),
         };
 
-        self.rotation += vr * dt2ms(dt) as f32;
+        self.rotation += vr * magnitude * dt2ms_f32(dt);
+    }
+}
+
+// how far `pos` is inside the box from `min` to `max`, negative outside it
+fn edge_distance(pos: V2, (min, max): (V2, V2)) -> f32 {
+    f32::min(f32::min(pos.x - min.x, max.x - pos.x),
+             f32::min(pos.y - min.y, max.y - pos.y))
+}
+
+// the flickering flame on row `row` of the flame sheet
+fn flame(row: usize) -> Rc<Animation> {
+    let first = row * FLAME_FRAMES;
+    Rc::new((1..FLAME_FRAMES).fold(Animation::new(Playback::PingPong, first, FLAME_FRAME_MS),
+                                   |anim, col| anim.frame(first + col, FLAME_FRAME_MS)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use input::{Axis, Input};
+    use input::gamepad::{GamepadDevice, MockGamepad};
+    use render::NullTextures;
+    use units::Direction;
+    use units::linear::V2;
+    use super::{Sprite, BULLET_VMAX, SHIP_ACCEL, SHIP_ROT, SHIP_VMAX};
+
+    fn ship() -> Sprite { Sprite::new(&mut NullTextures::new()) }
+
+    fn ms(n: u64) -> Duration { Duration::from_millis(n) }
+
+    fn close(a: f32, b: f32) -> bool { (a - b).abs() <= 1e-4 * b.abs().max(1e-9) }
+
+    #[test]
+    fn engine_output_scales_with_magnitude() {
+        let mut sprite = ship();
+        sprite.integrate(ms(10), Direction::Up, 0.25);
+        assert!(close(sprite.velocity().len(), SHIP_ACCEL * 0.25 * 10.0));
+
+        let mut sprite = ship();
+        sprite.integrate(ms(10), Direction::Left, 1.0);
+        assert!(close(sprite.velocity().len(), SHIP_ACCEL * 10.0));
+
+        // no amount of thrust exceeds the top speed
+        sprite.integrate(ms(100_000), Direction::Up, 1.0);
+        assert!(close(sprite.velocity().len(), SHIP_VMAX));
+    }
+
+    #[test]
+    fn turn_rate_scales_with_magnitude() {
+        let mut sprite = ship();
+        let start = sprite.rotation();
+
+        sprite.rotate(ms(10), Direction::Left, 0.5);
+        assert!(close(sprite.rotation() - start, SHIP_ROT * 0.5 * 10.0));
+
+        sprite.rotate(ms(10), Direction::Right, 0.5);
+        assert!((sprite.rotation() - start).abs() < 1e-6);
+    }
+
+    #[test]
+    fn bullets_fly_until_they_leave_the_screen() {
+        let mut sprite = ship();
+        sprite.set_bounds((V2::at(0.0, 0.0), V2::at(1.0, 1.0)));
+        sprite.vel = V2::at(0.0, SHIP_VMAX);
+
+        // bullets ignore the ship's own velocity
+        sprite.pewpew();
+        assert!(close(sprite.particles[0].vel.len(), BULLET_VMAX));
+
+        // half a screen at 0.7 u/s takes ~714ms
+        sprite.step_particles(ms(700));
+        assert_eq!(sprite.particle_count(), 1);
+        sprite.step_particles(ms(20));
+        assert_eq!(sprite.particle_count(), 0);
+    }
+
+    #[test]
+    fn sticks_steer_the_ship() {
+        let (mut pad, mut controller, mut sprite) = (MockGamepad::new(), Input::new(), ship());
+        let start = sprite.rotation();
+        controller.set_deadzone(0.2);
+
+        // half thrust on the left stick, full turn on the right stick
+        pad.set_axis(Axis::LeftY, 0.6);
+        pad.set_axis(Axis::RightX, -1.0);
+
+        let mut events = vec![];
+        pad.poll(&mut events);
+        for ev in events { controller.apply(ev) }
+        sprite.update(&controller, ms(10));
+
+        assert!(close(sprite.velocity().len(), SHIP_ACCEL * 0.5 * 10.0));
+        assert!(close(sprite.rotation() - start, SHIP_ROT * 10.0));
     }
 }
@@ -0,0 +1,119 @@
+use std::error;
+use std::fmt;
+use std::io;
+use std::result;
+
+use glium;
+use glium::texture::TextureCreationError;
+use glium::vertex::BufferCreationError;
+use image::ImageError;
+
+/// Anything which can go wrong while loading assets or drawing.
+///
+/// None of these are fatal on their own: a failed asset can be replaced
+/// by a placeholder, and a failed frame can be skipped.
+#[derive(Debug)]
+pub enum Error {
+    /// A file could not be read.
+    Io(io::Error),
+    /// An image could not be decoded.
+    Image(ImageError),
+    /// A file was read, but what it says doesn't make sense.
+    Parse(String),
+
+    /// The GPU could not allocate a texture.
+    Texture(TextureCreationError),
+    /// The GPU could not allocate a vertex buffer.
+    Buffer(BufferCreationError),
+    /// A batch had more verts (the count attached) than fit in the vertex buffer.
+    Batch(usize),
+    /// A shader program failed to compile or link.
+    Program(glium::ProgramCreationError),
+    /// A shader program's uniforms don't match the ones it was loaded w/.
+    Uniforms(String),
+    /// A draw call was rejected.
+    Draw(glium::DrawError),
+
+    /// Loading the asset at a path failed, the cause is attached.
+    Asset(String, Box<Error>),
+}
+
+pub type Result<T> = result::Result<T, Error>;
+
+impl Error {
+    /// Wraps `cause` w/ the path of the asset which was being loaded.
+    pub fn asset(path: &str, cause: Error) -> Self {
+        Error::Asset(path.to_string(), Box::new(cause))
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::Io(ref err)    => write!(f, "{}", err),
+            Error::Image(ref err) => write!(f, "{}", err),
+            Error::Parse(ref msg) => write!(f, "{}", msg),
+
+            Error::Texture(ref err) => write!(f, "could not allocate texture: {:?}", err),
+            Error::Buffer(ref err)  => write!(f, "could not allocate buffer: {:?}", err),
+            Error::Batch(verts)     => write!(f, "batch of {} verts does not fit the vertex buffer", verts),
+            Error::Program(ref err) => write!(f, "could not build shader: {:?}", err),
+            Error::Uniforms(ref msg) => write!(f, "{}", msg),
+            Error::Draw(ref err)    => write!(f, "could not draw: {:?}", err),
+
+            Error::Asset(ref path, ref cause) => write!(f, "{}: {}", path, cause),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn description(&self) -> &str {
+        match *self {
+            Error::Io(_)    => "i/o error",
+            Error::Image(_) => "could not decode image",
+            Error::Parse(_) => "could not parse file",
+
+            Error::Texture(_) => "could not allocate texture",
+            Error::Buffer(_)  => "could not allocate buffer",
+            Error::Batch(_)   => "batch does not fit the vertex buffer",
+            Error::Program(_) => "could not build shader",
+            Error::Uniforms(_) => "shader uniforms do not match",
+            Error::Draw(_)    => "could not draw",
+
+            Error::Asset(..)  => "could not load asset",
+        }
+    }
+
+    fn cause(&self) -> Option<&error::Error> {
+        match *self {
+            Error::Io(ref err)    => Some(err),
+            Error::Image(ref err) => Some(err),
+            Error::Asset(_, ref cause) => Some(&**cause),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self { Error::Io(err) }
+}
+
+impl From<ImageError> for Error {
+    fn from(err: ImageError) -> Self { Error::Image(err) }
+}
+
+impl From<TextureCreationError> for Error {
+    fn from(err: TextureCreationError) -> Self { Error::Texture(err) }
+}
+
+impl From<BufferCreationError> for Error {
+    fn from(err: BufferCreationError) -> Self { Error::Buffer(err) }
+}
+
+impl From<glium::ProgramCreationError> for Error {
+    fn from(err: glium::ProgramCreationError) -> Self { Error::Program(err) }
+}
+
+impl From<glium::DrawError> for Error {
+    fn from(err: glium::DrawError) -> Self { Error::Draw(err) }
+}
@@ -0,0 +1,65 @@
+use clock::{self, SIM_RATE_HZ};
+use console::Console;
+use entities::map::STAR_SEED;
+use input::{Bindings, Input, InputScript};
+use render::NullTextures;
+use sim::Simulation;
+
+static HEADLESS_TICKS: u64 = 600;
+
+/// Runs the simulation as fast as possible w/o opening a window. Input is
+/// supplied by `script`, the final state of the world is printed to stdout
+/// once all steps have been run.
+///
+/// Unless `ticks` is given this runs until the end of the script (if it
+/// was recorded) or for `HEADLESS_TICKS` steps otherwise.
+pub fn run(ticks: Option<u64>, script: InputScript, bindings: Bindings) {
+    simulate(ticks, script, bindings).dump();
+}
+
+/// Steps a simulation as described by `run()`, and hands it back.
+fn simulate(ticks: Option<u64>, mut script: InputScript, bindings: Bindings) -> Simulation {
+    let ticks = ticks.or(script.end()).unwrap_or(HEADLESS_TICKS);
+    let seed  = script.seed().unwrap_or(STAR_SEED);
+    let step  = script.timestep().unwrap_or_else(|| clock::step_for_hz(SIM_RATE_HZ));
+
+    let mut textures   = NullTextures::new();
+    let mut controller = Input::new();
+    let mut console    = Console::new();
+    let mut sim        = Simulation::new(&mut textures, seed);
+    controller.set_bindings(bindings);
+    script.configure(&mut controller);
+
+    println!("running {} ticks headless ...", ticks);
+    while sim.tick() < ticks {
+        script.apply(sim.tick(), &mut controller);
+        console.update(&mut controller);
+        sim.step(&controller, step);
+        controller.begin_new_frame();
+    }
+
+    sim
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+
+    use input::{Bindings, InputScript};
+    use super::simulate;
+
+    #[test]
+    fn thrust_then_reverse() {
+        let script = InputScript::load("assets/scripts/thrust_reverse.txt").unwrap();
+        let sim    = simulate(None, script, Bindings::default());
+        let (pos, vel) = (sim.player().position(), sim.player().velocity());
+
+        // thrusts up the screen, then flips around & burns most of that off
+        assert_eq!(sim.tick(), 480);
+        assert!((pos.x - 0.5).abs() < 1e-4);
+        assert!((pos.y - 0.8496).abs() < 1e-3);
+        assert!(vel.x.abs() < 1e-6 && vel.y > 0.0 && vel.y < 1e-3);
+        assert!((sim.player().rotation() - 1.5 * PI).abs() < 1e-3);
+        assert_eq!(sim.player().particle_count(), 0);
+    }
+}
@@ -0,0 +1,223 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+
+use input::{Key, MouseButton};
+use input::gamepad::{Axis, GamepadButton};
+
+/// Something the player can ask the game to do, independent of which
+/// key or button they have bound it to.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+    Thrust,
+    StrafeLeft,
+    StrafeRight,
+    RotateCCW,
+    RotateCW,
+    ReverseAutopilot,
+    Fire,
+}
+
+static ACTION_NAMES: &'static [(Action, &'static str)] = &[
+    (Action::Thrust,           "thrust"),
+    (Action::StrafeLeft,       "strafe_left"),
+    (Action::StrafeRight,      "strafe_right"),
+    (Action::RotateCCW,        "rotate_ccw"),
+    (Action::RotateCW,         "rotate_cw"),
+    (Action::ReverseAutopilot, "reverse_autopilot"),
+    (Action::Fire,             "fire"),
+];
+
+impl Action {
+    /// Looks up an action by the name used in config files.
+    pub fn from_name(name: &str) -> Option<Action> {
+        ACTION_NAMES.iter().find(|&&(_, n)| n == name).map(|&(action, _)| action)
+    }
+
+    /// The name used for this action in config files.
+    pub fn name(self) -> &'static str {
+        ACTION_NAMES.iter().find(|&&(action, _)| action == self).map_or("", |&(_, n)| n)
+    }
+}
+
+/// Which half of an analog axis a binding responds to.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AxisDir {
+    Positive,
+    Negative,
+}
+
+/// A single key, button, or axis which can trigger an action.
+/// A chord is a pair of keys which must be held together.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Binding {
+    Key(Key),
+    Mouse(MouseButton),
+    Pad(GamepadButton),
+    Axis(Axis, AxisDir),
+    Chord(Key, Key),
+}
+
+impl Binding {
+    /// Looks up a key, button, or axis by the name used in config files.
+    /// Axes are named w/ the direction they are pushed, e.g: `LeftY+`,
+    /// while chords join two keys w/ a plus, e.g: `LShift+S`.
+    pub fn from_name(name: &str) -> Option<Binding> {
+        Key::from_name(name).map(Binding::Key)
+            .or_else(|| MouseButton::from_name(name).map(Binding::Mouse))
+            .or_else(|| GamepadButton::from_name(name).map(Binding::Pad))
+            .or_else(|| parse_axis(name))
+            .or_else(|| parse_chord(name))
+    }
+
+    /// The name `from_name()` reads this binding back from.
+    pub fn name(self) -> String {
+        match self {
+            Binding::Key(key)      => key.name().to_string(),
+            Binding::Mouse(button) => button.name(),
+            Binding::Pad(button)   => button.name().to_string(),
+            Binding::Axis(axis, AxisDir::Positive) => format!("{}+", axis.name()),
+            Binding::Axis(axis, AxisDir::Negative) => format!("{}-", axis.name()),
+            Binding::Chord(first, second) => format!("{}+{}", first.name(), second.name()),
+        }
+    }
+}
+
+/// Maps each action to the keys and buttons which trigger it.
+///
+/// Bindings are loaded from a plain text file w/ one action per line,
+/// any action not listed in the file keeps its default bindings:
+///
+/// ```text
+/// # action = binding, binding ...
+/// thrust = W, Up
+/// fire   = Space, MouseLeft
+/// ```
+#[derive(Clone)]
+pub struct Bindings {
+    map: HashMap<Action, Vec<Binding>>,
+}
+
+impl Bindings {
+    /// Builds a set of bindings w/ nothing bound.
+    pub fn empty() -> Self {
+        Bindings { map: HashMap::new() }
+    }
+
+    /// Reads bindings from disk, see `Bindings` for the file format.
+    pub fn load(path: &str) -> io::Result<Self> {
+        let file = File::open(path)?;
+        let mut bindings = Bindings::default();
+
+        for (line_no, line) in BufReader::new(file).lines().enumerate() {
+            let line = line?;
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() { continue }
+
+            match bindings.parse_line(line) {
+                Some(()) => {},
+                None => return Err(io::Error::new(io::ErrorKind::InvalidData,
+                                                  format!("{}:{}: bad binding `{}`", path, line_no + 1, line))),
+            }
+        }
+
+        Ok(bindings)
+    }
+
+    /// Reads bindings from disk, falling back to the defaults (and
+    /// reporting why) if the file is missing or malformed.
+    pub fn load_or_default(path: &str) -> Self {
+        Bindings::load(path).unwrap_or_else(|err| {
+            println!("could not load bindings, using defaults: {}", err);
+            Bindings::default()
+        })
+    }
+
+    /// Adds `binding` as a trigger for `action`.
+    pub fn bind(&mut self, action: Action, binding: Binding) {
+        self.map.entry(action).or_insert_with(Vec::new).push(binding);
+    }
+
+    /// Replaces the bindings of one action w/ those on a line of a bindings
+    /// file, e.g: `thrust = W, Up`.
+    pub fn parse_line(&mut self, line: &str) -> Option<()> {
+        let (action, binds) = parse_line(line)?;
+        self.map.insert(action, binds);
+        Some(())
+    }
+
+    /// Every action's bindings, one line per action as `parse_line()` reads them.
+    pub fn lines(&self) -> Vec<String> {
+        ACTION_NAMES.iter().map(|&(action, _)| {
+            let binds: Vec<String> = self.get(action).iter().map(|bind| bind.name()).collect();
+            format!("{} = {}", action.name(), binds.join(", "))
+        }).collect()
+    }
+
+    /// Every key or button bound to `action`.
+    pub fn get(&self, action: Action) -> &[Binding] {
+        match self.map.get(&action) {
+            Some(binds) => &binds[..],
+            None        => &[],
+        }
+    }
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        let mut bindings = Bindings::empty();
+
+        bindings.bind(Action::Thrust,           Binding::Key(Key::W));
+        bindings.bind(Action::StrafeLeft,       Binding::Key(Key::A));
+        bindings.bind(Action::StrafeRight,      Binding::Key(Key::D));
+        bindings.bind(Action::RotateCCW,        Binding::Key(Key::Q));
+        bindings.bind(Action::RotateCW,         Binding::Key(Key::E));
+        bindings.bind(Action::ReverseAutopilot, Binding::Key(Key::S));
+        bindings.bind(Action::Fire,             Binding::Key(Key::Space));
+        bindings.bind(Action::Fire,             Binding::Mouse(MouseButton::Left));
+
+        bindings.bind(Action::Thrust,           Binding::Axis(Axis::LeftY,  AxisDir::Positive));
+        bindings.bind(Action::StrafeLeft,       Binding::Axis(Axis::LeftX,  AxisDir::Negative));
+        bindings.bind(Action::StrafeRight,      Binding::Axis(Axis::LeftX,  AxisDir::Positive));
+        bindings.bind(Action::RotateCCW,        Binding::Axis(Axis::RightX, AxisDir::Negative));
+        bindings.bind(Action::RotateCW,         Binding::Axis(Axis::RightX, AxisDir::Positive));
+        bindings.bind(Action::ReverseAutopilot, Binding::Pad(GamepadButton::East));
+        bindings.bind(Action::Fire,             Binding::Pad(GamepadButton::South));
+
+        bindings
+    }
+}
+
+fn parse_line(line: &str) -> Option<(Action, Vec<Binding>)> {
+    let mut halves = line.splitn(2, '=');
+    let action = Action::from_name(halves.next()?.trim())?;
+
+    let mut binds = vec![];
+    for name in halves.next()?.split(',') {
+        let name = name.trim();
+        if name.is_empty() { continue }
+        binds.push(Binding::from_name(name)?);
+    }
+
+    Some((action, binds))
+}
+
+fn parse_axis(name: &str) -> Option<Binding> {
+    let dir = if name.ends_with('+') {
+        AxisDir::Positive
+    } else if name.ends_with('-') {
+        AxisDir::Negative
+    } else {
+        return None
+    };
+
+    let axis = Axis::from_name(&name[..name.len() - 1])?;
+    Some(Binding::Axis(axis, dir))
+}
+
+fn parse_chord(name: &str) -> Option<Binding> {
+    let mut keys = name.splitn(2, '+');
+    let first  = Key::from_name(keys.next()?.trim())?;
+    let second = Key::from_name(keys.next()?.trim())?;
+    Some(Binding::Chord(first, second))
+}
@@ -0,0 +1,162 @@
+use input::{Axis, AxisDir, GamepadButton, Key, MouseButton};
+
+// enough room for every key, or every mouse button glutin can report.
+// (3 named buttons + 256 others)
+const BITSET_WORDS: usize = 5;
+
+/// Number of distinct values a `BitSet` can hold.
+pub const BITSET_BITS: usize = BITSET_WORDS * 64;
+
+/// A fixed size set of keys or buttons, stored one bit apiece.
+///
+/// Queries are a shift & mask, and clearing the set is a memset, so
+/// tracking input never touches the heap once the set has been built.
+#[derive(Copy, Clone)]
+pub struct BitSet {
+    words: [u64; BITSET_WORDS],
+}
+
+impl BitSet {
+    pub fn new() -> Self { BitSet { words: [0; BITSET_WORDS] } }
+
+    pub fn insert<T: BitIndex>(&mut self, item: T) {
+        let idx = item.bit_index();
+        self.words[idx / 64] |= 1 << (idx % 64);
+    }
+
+    pub fn remove<T: BitIndex>(&mut self, item: T) {
+        let idx = item.bit_index();
+        self.words[idx / 64] &= !(1 << (idx % 64));
+    }
+
+    pub fn contains<T: BitIndex>(&self, item: T) -> bool {
+        let idx = item.bit_index();
+        self.words[idx / 64] & (1 << (idx % 64)) != 0
+    }
+
+    pub fn clear(&mut self) { self.words = [0; BITSET_WORDS]; }
+
+    /// Iterates over the index of every item in the set, in order.
+    pub fn iter(&self) -> Iter { Iter { set: *self, idx: 0 } }
+}
+
+pub struct Iter {
+    set: BitSet,
+    idx: usize,
+}
+
+impl Iterator for Iter {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        while self.idx < BITSET_BITS {
+            let idx = self.idx;
+            self.idx += 1;
+            if self.set.words[idx / 64] & (1 << (idx % 64)) != 0 { return Some(idx) }
+        }
+
+        None
+    }
+}
+
+/// Anything which can be stored in a `BitSet`, each value must map
+/// to a unique index below `BITSET_BITS`.
+pub trait BitIndex: Copy {
+    fn bit_index(self) -> usize;
+
+    /// The inverse of `bit_index()`, responds `None` for unused indices.
+    fn from_bit_index(idx: usize) -> Option<Self>;
+}
+
+impl BitIndex for Key {
+    fn bit_index(self) -> usize { self as usize }
+
+    fn from_bit_index(idx: usize) -> Option<Key> { Key::from_index(idx) }
+}
+
+impl BitIndex for MouseButton {
+    fn bit_index(self) -> usize {
+        match self {
+            MouseButton::Left     => 0,
+            MouseButton::Right    => 1,
+            MouseButton::Middle   => 2,
+            MouseButton::Other(n) => 3 + n as usize,
+        }
+    }
+
+    fn from_bit_index(idx: usize) -> Option<MouseButton> {
+        match idx {
+            0 => Some(MouseButton::Left),
+            1 => Some(MouseButton::Right),
+            2 => Some(MouseButton::Middle),
+            n if n < 3 + 256 => Some(MouseButton::Other((n - 3) as u8)),
+            _ => None,
+        }
+    }
+}
+
+impl BitIndex for GamepadButton {
+    fn bit_index(self) -> usize { self as usize }
+
+    fn from_bit_index(idx: usize) -> Option<GamepadButton> { GamepadButton::from_index(idx) }
+}
+
+// each half of an axis is pressed separately, so they get a bit apiece
+impl BitIndex for (Axis, AxisDir) {
+    fn bit_index(self) -> usize {
+        match self.1 {
+            AxisDir::Positive => self.0 as usize * 2,
+            AxisDir::Negative => self.0 as usize * 2 + 1,
+        }
+    }
+
+    fn from_bit_index(idx: usize) -> Option<(Axis, AxisDir)> {
+        let dir = if idx % 2 == 0 { AxisDir::Positive } else { AxisDir::Negative };
+        Axis::from_index(idx / 2).map(|axis| (axis, dir))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use input::{Key, MouseButton};
+    use super::{BitIndex, BitSet};
+
+    #[test]
+    fn inserts_and_removes() {
+        let mut set = BitSet::new();
+        set.insert(Key::W);
+        set.insert(Key::Space);
+
+        assert!(set.contains(Key::W) && set.contains(Key::Space));
+        assert!(!set.contains(Key::S));
+
+        set.remove(Key::W);
+        assert!(!set.contains(Key::W) && set.contains(Key::Space));
+
+        set.clear();
+        assert!(!set.contains(Key::Space));
+        assert_eq!(set.iter().count(), 0);
+    }
+
+    #[test]
+    fn reaches_the_last_word() {
+        let mut set = BitSet::new();
+        let last = MouseButton::Other(255);
+
+        set.insert(last);
+        assert!(set.contains(last));
+        assert!(!set.contains(MouseButton::Other(254)));
+        assert_eq!(MouseButton::from_bit_index(last.bit_index()), Some(last));
+    }
+
+    #[test]
+    fn iterates_in_order() {
+        let mut set = BitSet::new();
+        for &button in &[MouseButton::Other(100), MouseButton::Left, MouseButton::Other(0)] {
+            set.insert(button);
+        }
+
+        let buttons: Vec<_> = set.iter().filter_map(MouseButton::from_bit_index).collect();
+        assert_eq!(buttons, vec![MouseButton::Left, MouseButton::Other(0), MouseButton::Other(100)]);
+    }
+}
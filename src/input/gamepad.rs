@@ -0,0 +1,204 @@
+use input::InputEvent;
+
+/// An analog axis on a gamepad. Sticks range from -1.0 to 1.0 (x grows
+/// right, y grows up) while triggers range from 0.0 to 1.0.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Axis {
+    LeftX,
+    LeftY,
+    RightX,
+    RightY,
+    LeftTrigger,
+    RightTrigger,
+}
+
+/// Number of variants in `Axis`, used to size per-axis storage.
+pub const AXIS_COUNT: usize = 6;
+
+static AXIS_NAMES: &'static [(Axis, &'static str)] = &[
+    (Axis::LeftX,        "LeftX"),
+    (Axis::LeftY,        "LeftY"),
+    (Axis::RightX,       "RightX"),
+    (Axis::RightY,       "RightY"),
+    (Axis::LeftTrigger,  "LeftTrigger"),
+    (Axis::RightTrigger, "RightTrigger"),
+];
+
+impl Axis {
+    /// Looks up an axis by the name used in scripts & config files.
+    pub fn from_name(name: &str) -> Option<Axis> {
+        AXIS_NAMES.iter().find(|&&(_, n)| n == name).map(|&(axis, _)| axis)
+    }
+
+    /// The name used for this axis in scripts & config files.
+    pub fn name(self) -> &'static str { AXIS_NAMES[self as usize].1 }
+
+    /// Looks up an axis by its position in the enumeration.
+    pub fn from_index(idx: usize) -> Option<Axis> { AXIS_NAMES.get(idx).map(|&(axis, _)| axis) }
+}
+
+/// A digital button on a gamepad, named by its position on the pad.
+/// (e.g: `South` is `A` on an XBox pad, `Cross` on a Playstation pad.)
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum GamepadButton {
+    South, East, North, West,
+    LeftBumper, RightBumper,
+    LeftStick, RightStick,
+    Select, Start,
+    DPadUp, DPadDown, DPadLeft, DPadRight,
+}
+
+static BUTTON_NAMES: &'static [(GamepadButton, &'static str)] = &[
+    (GamepadButton::South,       "PadSouth"),
+    (GamepadButton::East,        "PadEast"),
+    (GamepadButton::North,       "PadNorth"),
+    (GamepadButton::West,        "PadWest"),
+    (GamepadButton::LeftBumper,  "PadLeftBumper"),
+    (GamepadButton::RightBumper, "PadRightBumper"),
+    (GamepadButton::LeftStick,   "PadLeftStick"),
+    (GamepadButton::RightStick,  "PadRightStick"),
+    (GamepadButton::Select,      "PadSelect"),
+    (GamepadButton::Start,       "PadStart"),
+    (GamepadButton::DPadUp,      "PadUp"),
+    (GamepadButton::DPadDown,    "PadDown"),
+    (GamepadButton::DPadLeft,    "PadLeft"),
+    (GamepadButton::DPadRight,   "PadRight"),
+];
+
+impl GamepadButton {
+    /// Looks up a button by the name used in scripts & config files.
+    pub fn from_name(name: &str) -> Option<GamepadButton> {
+        BUTTON_NAMES.iter().find(|&&(_, n)| n == name).map(|&(button, _)| button)
+    }
+
+    /// The name used for this button in scripts & config files.
+    pub fn name(self) -> &'static str { BUTTON_NAMES[self as usize].1 }
+
+    /// Looks up a button by its position in the enumeration.
+    pub fn from_index(idx: usize) -> Option<GamepadButton> { BUTTON_NAMES.get(idx).map(|&(button, _)| button) }
+}
+
+/// Anything which can report gamepad input, be it real hardware or not.
+pub trait GamepadDevice {
+    /// Appends any input which occurred since the last poll to `events`.
+    fn poll(&mut self, events: &mut Vec<InputEvent>);
+}
+
+/// A gamepad which only reports what it is told to. This can stand in
+/// for real hardware to drive the input layer from tests.
+#[cfg(test)]
+pub struct MockGamepad {
+    pending: Vec<InputEvent>,
+}
+
+#[cfg(test)]
+impl MockGamepad {
+    pub fn new() -> Self { MockGamepad { pending: vec![] } }
+
+    pub fn press(&mut self, button: GamepadButton) {
+        self.pending.push(InputEvent::PadDown(button));
+    }
+
+    pub fn release(&mut self, button: GamepadButton) {
+        self.pending.push(InputEvent::PadUp(button));
+    }
+
+    pub fn set_axis(&mut self, axis: Axis, value: f32) {
+        self.pending.push(InputEvent::PadAxis(axis, value));
+    }
+}
+
+#[cfg(test)]
+impl GamepadDevice for MockGamepad {
+    fn poll(&mut self, events: &mut Vec<InputEvent>) {
+        events.extend(self.pending.drain(..));
+    }
+}
+
+/// Opens every gamepad backend compiled into the game.
+/// (Without the `gamepad` feature there are none.)
+pub fn connect() -> Vec<Box<GamepadDevice>> {
+    #[allow(unused_mut)]
+    let mut devices: Vec<Box<GamepadDevice>> = vec![];
+
+    #[cfg(feature = "gamepad")]
+    match super::gilrs::GilrsGamepad::new() {
+        Some(pad) => devices.push(Box::new(pad)),
+        None => println!("could not initialize gamepad support"),
+    }
+
+    devices
+}
+
+/// Zeroes out `value` if it falls within `deadzone` of the center, then
+/// rescales the remainder so the output still covers the full range.
+pub fn apply_deadzone(value: f32, deadzone: f32) -> f32 {
+    let magnitude = value.abs();
+    if magnitude <= deadzone { return 0.0 }
+
+    value.signum() * ((magnitude - deadzone) / (1.0 - deadzone)).min(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use input::{Action, Input};
+    use super::{apply_deadzone, Axis, GamepadDevice, MockGamepad};
+
+    // starts a new frame of `controller` holding everything `pad` queued up
+    fn poll(pad: &mut MockGamepad, controller: &mut Input) {
+        let mut events = vec![];
+        pad.poll(&mut events);
+
+        controller.begin_new_frame();
+        for ev in events { controller.apply(ev) }
+    }
+
+    fn close(a: f32, b: f32) -> bool { (a - b).abs() < 1e-5 }
+
+    #[test]
+    fn deadzone_rescales_the_remaining_range() {
+        assert_eq!(apply_deadzone(0.1, 0.2), 0.0);
+        assert_eq!(apply_deadzone(-0.2, 0.2), 0.0);
+        assert!(close(apply_deadzone(0.6, 0.2), 0.5));
+        assert!(close(apply_deadzone(-1.0, 0.2), -1.0));
+    }
+
+    #[test]
+    fn deadzone_is_clamped() {
+        let (mut pad, mut controller) = (MockGamepad::new(), Input::new());
+
+        controller.set_deadzone(1.0);
+        pad.set_axis(Axis::LeftX, 1.0);
+        poll(&mut pad, &mut controller);
+        assert!(close(controller.axis(Axis::LeftX), 1.0));
+
+        controller.set_deadzone(-0.5);
+        pad.set_axis(Axis::LeftX, 0.25);
+        poll(&mut pad, &mut controller);
+        assert!(close(controller.axis(Axis::LeftX), 0.25));
+    }
+
+    #[test]
+    fn axes_drive_bound_actions() {
+        let (mut pad, mut controller) = (MockGamepad::new(), Input::new());
+        controller.set_deadzone(0.2);
+
+        // halfway past the deadzone on the thrust stick
+        pad.set_axis(Axis::LeftY, 0.6);
+        pad.set_axis(Axis::LeftX, -1.0);
+        poll(&mut pad, &mut controller);
+
+        assert!(close(controller.action_value(Action::Thrust), 0.5));
+        assert!(controller.was_action_pressed(Action::Thrust));
+        assert!(close(controller.action_value(Action::StrafeLeft), 1.0));
+        assert_eq!(controller.action_value(Action::StrafeRight), 0.0);
+
+        // easing off below the press threshold releases the action
+        pad.set_axis(Axis::LeftY, 0.3);
+        poll(&mut pad, &mut controller);
+
+        assert!(controller.was_action_released(Action::Thrust));
+        assert!(!controller.is_action_held(Action::Thrust));
+        assert!(close(controller.action_value(Action::Thrust), 0.125));
+    }
+}
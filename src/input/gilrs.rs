@@ -0,0 +1,72 @@
+use gilrs::{self, EventType, Gilrs};
+
+use input::InputEvent;
+use input::gamepad::{Axis, GamepadButton, GamepadDevice};
+
+/// Reads every gamepad connected to the system through `gilrs`.
+/// Input from all connected pads is merged as if it came from one.
+pub struct GilrsGamepad {
+    ctx: Gilrs,
+}
+
+impl GilrsGamepad {
+    pub fn new() -> Option<Self> {
+        Gilrs::new().ok().map(|ctx| GilrsGamepad { ctx: ctx })
+    }
+}
+
+impl GamepadDevice for GilrsGamepad {
+    fn poll(&mut self, events: &mut Vec<InputEvent>) {
+        while let Some(gilrs::Event { event, .. }) = self.ctx.next_event() {
+            let input_ev = match event {
+                EventType::ButtonPressed(button, _)  => translate_button(button).map(InputEvent::PadDown),
+                EventType::ButtonReleased(button, _) => translate_button(button).map(InputEvent::PadUp),
+
+                // analog triggers are reported as buttons w/ a value
+                EventType::ButtonChanged(gilrs::Button::LeftTrigger2, value, _)  => Some(InputEvent::PadAxis(Axis::LeftTrigger, value)),
+                EventType::ButtonChanged(gilrs::Button::RightTrigger2, value, _) => Some(InputEvent::PadAxis(Axis::RightTrigger, value)),
+
+                EventType::AxisChanged(axis, value, _) => translate_axis(axis).map(|axis| InputEvent::PadAxis(axis, value)),
+                _ => None,
+            };
+
+            if let Some(input_ev) = input_ev { events.push(input_ev); }
+        }
+    }
+}
+
+fn translate_button(button: gilrs::Button) -> Option<GamepadButton> {
+    let button = match button {
+        gilrs::Button::South => GamepadButton::South,
+        gilrs::Button::East  => GamepadButton::East,
+        gilrs::Button::North => GamepadButton::North,
+        gilrs::Button::West  => GamepadButton::West,
+
+        gilrs::Button::LeftTrigger  => GamepadButton::LeftBumper,
+        gilrs::Button::RightTrigger => GamepadButton::RightBumper,
+        gilrs::Button::LeftThumb    => GamepadButton::LeftStick,
+        gilrs::Button::RightThumb   => GamepadButton::RightStick,
+
+        gilrs::Button::Select => GamepadButton::Select,
+        gilrs::Button::Start  => GamepadButton::Start,
+
+        gilrs::Button::DPadUp    => GamepadButton::DPadUp,
+        gilrs::Button::DPadDown  => GamepadButton::DPadDown,
+        gilrs::Button::DPadLeft  => GamepadButton::DPadLeft,
+        gilrs::Button::DPadRight => GamepadButton::DPadRight,
+
+        _ => return None,
+    };
+
+    Some(button)
+}
+
+fn translate_axis(axis: gilrs::Axis) -> Option<Axis> {
+    match axis {
+        gilrs::Axis::LeftStickX  => Some(Axis::LeftX),
+        gilrs::Axis::LeftStickY  => Some(Axis::LeftY),
+        gilrs::Axis::RightStickX => Some(Axis::RightX),
+        gilrs::Axis::RightStickY => Some(Axis::RightY),
+        _ => None,
+    }
+}
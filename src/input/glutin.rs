@@ -0,0 +1,93 @@
+use glium::glutin::{ElementState, Event, MouseScrollDelta, VirtualKeyCode as VKC};
+use glium::glutin::MouseButton as GlutinButton;
+
+use input::{InputEvent, Key, MouseButton};
+
+// touchpads report scrolling in pixels, this is roughly how many
+// pixels a single notch of a mouse wheel would scroll.
+static PIXELS_PER_LINE: f32 = 16.0;
+
+// NOTE: this is the only part of the input layer which knows about glutin,
+//       porting to another windowing library should only require a new
+//       translation module along these lines.
+
+/// Translates a window system event into one the input layer understands.
+/// Responds `None` for events which are not input related, or involve keys
+/// megumin does not know about.
+pub fn translate_event(ev: &Event) -> Option<InputEvent> {
+    match *ev {
+        Event::KeyboardInput(ElementState::Pressed,  _, Some(vkc)) => translate_key(vkc).map(InputEvent::KeyDown),
+        Event::KeyboardInput(ElementState::Released, _, Some(vkc)) => translate_key(vkc).map(InputEvent::KeyUp),
+
+        // mouse (x grows right, y grows downward)
+        Event::MouseMoved(mx, my) => Some(InputEvent::CursorMoved(mx, my)),
+        Event::MouseInput(ElementState::Pressed,  button) => Some(InputEvent::ButtonDown(translate_button(button))),
+        Event::MouseInput(ElementState::Released, button) => Some(InputEvent::ButtonUp(translate_button(button))),
+        Event::MouseWheel(MouseScrollDelta::LineDelta(dx, dy), _)  => Some(InputEvent::Wheel(dx, dy)),
+        Event::MouseWheel(MouseScrollDelta::PixelDelta(dx, dy), _) => Some(InputEvent::Wheel(dx / PIXELS_PER_LINE,
+                                                                                            dy / PIXELS_PER_LINE)),
+
+        // text (already composed by the OS / IME)
+        Event::ReceivedCharacter(ch) => Some(InputEvent::Char(ch)),
+
+        // window state (suspended covers minimizing on some platforms)
+        Event::Focused(focused)     => Some(InputEvent::Focus(focused)),
+        Event::Suspended(suspended) => Some(InputEvent::Focus(!suspended)),
+        Event::Resized(w, h)        => Some(InputEvent::Resized(w, h)),
+
+        _ => None,
+    }
+}
+
+pub fn translate_button(button: GlutinButton) -> MouseButton {
+    match button {
+        GlutinButton::Left     => MouseButton::Left,
+        GlutinButton::Right    => MouseButton::Right,
+        GlutinButton::Middle   => MouseButton::Middle,
+        GlutinButton::Other(n) => MouseButton::Other(n),
+    }
+}
+
+pub fn translate_key(vkc: VKC) -> Option<Key> {
+    let key = match vkc {
+        VKC::A => Key::A, VKC::B => Key::B, VKC::C => Key::C, VKC::D => Key::D,
+        VKC::E => Key::E, VKC::F => Key::F, VKC::G => Key::G, VKC::H => Key::H,
+        VKC::I => Key::I, VKC::J => Key::J, VKC::K => Key::K, VKC::L => Key::L,
+        VKC::M => Key::M, VKC::N => Key::N, VKC::O => Key::O, VKC::P => Key::P,
+        VKC::Q => Key::Q, VKC::R => Key::R, VKC::S => Key::S, VKC::T => Key::T,
+        VKC::U => Key::U, VKC::V => Key::V, VKC::W => Key::W, VKC::X => Key::X,
+        VKC::Y => Key::Y, VKC::Z => Key::Z,
+
+        VKC::Key0 => Key::Key0, VKC::Key1 => Key::Key1, VKC::Key2 => Key::Key2,
+        VKC::Key3 => Key::Key3, VKC::Key4 => Key::Key4, VKC::Key5 => Key::Key5,
+        VKC::Key6 => Key::Key6, VKC::Key7 => Key::Key7, VKC::Key8 => Key::Key8,
+        VKC::Key9 => Key::Key9,
+
+        VKC::F1 => Key::F1, VKC::F2  => Key::F2,  VKC::F3  => Key::F3,  VKC::F4  => Key::F4,
+        VKC::F5 => Key::F5, VKC::F6  => Key::F6,  VKC::F7  => Key::F7,  VKC::F8  => Key::F8,
+        VKC::F9 => Key::F9, VKC::F10 => Key::F10, VKC::F11 => Key::F11, VKC::F12 => Key::F12,
+
+        VKC::Up => Key::Up, VKC::Down => Key::Down, VKC::Left => Key::Left, VKC::Right => Key::Right,
+
+        VKC::Escape => Key::Escape, VKC::Tab => Key::Tab, VKC::Space => Key::Space,
+        VKC::Back   => Key::Back,   VKC::Return => Key::Return,
+
+        VKC::Insert => Key::Insert, VKC::Delete   => Key::Delete,
+        VKC::Home   => Key::Home,   VKC::End      => Key::End,
+        VKC::PageUp => Key::PageUp, VKC::PageDown => Key::PageDown,
+
+        VKC::LShift   => Key::LShift,   VKC::RShift   => Key::RShift,
+        VKC::LControl => Key::LControl, VKC::RControl => Key::RControl,
+        VKC::LAlt | VKC::LMenu => Key::LAlt,
+        VKC::RAlt | VKC::RMenu => Key::RAlt,
+
+        VKC::Grave     => Key::Grave,     VKC::Minus      => Key::Minus,      VKC::Equals    => Key::Equals,
+        VKC::LBracket  => Key::LBracket,  VKC::RBracket   => Key::RBracket,   VKC::Backslash => Key::Backslash,
+        VKC::Semicolon => Key::Semicolon, VKC::Apostrophe => Key::Apostrophe,
+        VKC::Comma     => Key::Comma,     VKC::Period     => Key::Period,     VKC::Slash     => Key::Slash,
+
+        _ => return None,
+    };
+
+    Some(key)
+}
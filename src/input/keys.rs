@@ -0,0 +1,90 @@
+// Declares the `Key` enumeration alongside a table of the names used
+// for each key in scripts & config files. The table is generated in
+// the same order as the enumeration, so it can be indexed by `Key`.
+macro_rules! keys {
+    ($($key:ident => $name:expr),+ $(,)*) => {
+        /// A physical key on the keyboard, independent of any windowing library.
+        #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+        pub enum Key { $($key),+ }
+
+        static KEY_NAMES: &'static [(Key, &'static str)] = &[ $((Key::$key, $name)),+ ];
+    }
+}
+
+keys! {
+    A => "A", B => "B", C => "C", D => "D", E => "E", F => "F", G => "G",
+    H => "H", I => "I", J => "J", K => "K", L => "L", M => "M", N => "N",
+    O => "O", P => "P", Q => "Q", R => "R", S => "S", T => "T", U => "U",
+    V => "V", W => "W", X => "X", Y => "Y", Z => "Z",
+
+    Key0 => "0", Key1 => "1", Key2 => "2", Key3 => "3", Key4 => "4",
+    Key5 => "5", Key6 => "6", Key7 => "7", Key8 => "8", Key9 => "9",
+
+    F1 => "F1", F2  => "F2",  F3  => "F3",  F4  => "F4",
+    F5 => "F5", F6  => "F6",  F7  => "F7",  F8  => "F8",
+    F9 => "F9", F10 => "F10", F11 => "F11", F12 => "F12",
+
+    Up => "Up", Down => "Down", Left => "Left", Right => "Right",
+
+    Escape => "Escape", Tab => "Tab", Space => "Space",
+    Back   => "Back",   Return => "Return",
+
+    Insert => "Insert", Delete => "Delete",
+    Home   => "Home",   End    => "End",
+    PageUp => "PageUp", PageDown => "PageDown",
+
+    LShift   => "LShift",   RShift   => "RShift",
+    LControl => "LControl", RControl => "RControl",
+    LAlt     => "LAlt",     RAlt     => "RAlt",
+
+    Grave      => "Grave",      Minus     => "Minus",     Equals    => "Equals",
+    LBracket   => "LBracket",   RBracket  => "RBracket",  Backslash => "Backslash",
+    Semicolon  => "Semicolon",  Apostrophe => "Apostrophe",
+    Comma      => "Comma",      Period    => "Period",    Slash     => "Slash",
+}
+
+impl Key {
+    /// Looks up a key by the name used in scripts & config files.
+    pub fn from_name(name: &str) -> Option<Key> {
+        KEY_NAMES.iter().find(|&&(_, n)| n == name).map(|&(key, _)| key)
+    }
+
+    /// The name used for this key in scripts & config files.
+    pub fn name(self) -> &'static str { KEY_NAMES[self as usize].1 }
+
+    /// Looks up a key by its position in the enumeration.
+    pub fn from_index(idx: usize) -> Option<Key> { KEY_NAMES.get(idx).map(|&(key, _)| key) }
+}
+
+/// A button on the mouse, independent of any windowing library.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum MouseButton {
+    Left,
+    Right,
+    Middle,
+    Other(u8),
+}
+
+impl MouseButton {
+    /// Looks up a mouse button by the name used in scripts & config files.
+    /// (`MouseLeft`, `MouseRight`, `MouseMiddle`, or `Mouse<N>` for others.)
+    pub fn from_name(name: &str) -> Option<MouseButton> {
+        match name {
+            "MouseLeft"   => Some(MouseButton::Left),
+            "MouseRight"  => Some(MouseButton::Right),
+            "MouseMiddle" => Some(MouseButton::Middle),
+            _ if name.starts_with("Mouse") => name["Mouse".len()..].parse().ok().map(MouseButton::Other),
+            _ => None,
+        }
+    }
+
+    /// The name used for this button in scripts & config files.
+    pub fn name(self) -> String {
+        match self {
+            MouseButton::Left     => "MouseLeft".to_string(),
+            MouseButton::Right    => "MouseRight".to_string(),
+            MouseButton::Middle   => "MouseMiddle".to_string(),
+            MouseButton::Other(n) => format!("Mouse{}", n),
+        }
+    }
+}
@@ -0,0 +1,562 @@
+use camera::DEFAULT_VIEWPORT;
+
+use self::bitset::{BitIndex, BitSet};
+use self::text::TextBuffer;
+use self::timing::PressTimes;
+
+pub use self::actions::{Action, AxisDir, Binding, Bindings};
+pub use self::gamepad::{Axis, GamepadButton};
+pub use self::keys::{Key, MouseButton};
+pub use self::record::InputRecorder;
+pub use self::script::InputScript;
+
+pub mod actions;
+mod bitset;
+pub mod gamepad;
+#[cfg(feature = "gamepad")]
+pub mod gilrs;
+pub mod glutin;
+pub mod keys;
+pub mod record;
+pub mod script;
+mod text;
+mod timing;
+
+// stick movement smaller than this is treated as noise
+static DEFAULT_DEADZONE: f32 = 0.15;
+
+// a deadzone covering the whole stick would leave nothing to rescale
+static MAX_DEADZONE: f32 = 0.95;
+
+// an axis pushed at least this far counts as "held" for digital queries
+static AXIS_PRESS_THRESHOLD: f32 = 0.5;
+
+/// A single input event, regardless of where it came from.
+/// (e.g: the window system, a script, a recorded session.)
+#[derive(Copy, Clone, Debug)]
+pub enum InputEvent {
+    KeyDown(Key),
+    KeyUp(Key),
+    ButtonDown(MouseButton),
+    ButtonUp(MouseButton),
+    CursorMoved(i32, i32),
+    Wheel(f32, f32),
+    PadDown(GamepadButton),
+    PadUp(GamepadButton),
+    PadAxis(Axis, f32),
+    Char(char),
+    Focus(bool),
+    Resized(u32, u32),
+}
+
+/// Responds to inquiries regarding three sets of keyboard, mouse & gamepad input.
+///
+///- Pressed keys
+///- Released keys
+///- Held keys
+///
+/// The step at which each key & button was pressed is also remembered,
+/// so holds can be timed and double taps detected. Steps are counted by
+/// calls to `begin_new_frame()`.
+///
+/// When the window loses focus every held key & button is released, since
+/// the window system will not report releases which happen in the background.
+///
+/// While in text mode typed characters are collected into a line of text,
+/// and keyboard bindings stop triggering actions. (Raw key queries still
+/// respond as normal.)
+///
+/// Each set is a fixed size `BitSet`, so buffering input never allocates.
+///
+/// Gamepad axes are also tracked, these report an analog value once the
+/// deadzone has been applied.
+///
+/// These can also be queried in terms of `Action`s, in which case the
+/// input's `Bindings` decide which keys, buttons & axes are consulted.
+pub struct Input {
+	pressed_keys:   BitSet,
+	released_keys:  BitSet,
+	held_keys:      BitSet,
+
+    pressed_buttons:  BitSet,
+    released_buttons: BitSet,
+    held_buttons:     BitSet,
+
+    pressed_pad:  BitSet,
+    released_pad: BitSet,
+    held_pad:     BitSet,
+
+    key_times:    PressTimes,
+    button_times: PressTimes,
+    pad_times:    PressTimes,
+    axis_times:   PressTimes,
+    tick:         u64,
+
+    axes:         [f32; gamepad::AXIS_COUNT],
+    prev_axes:    [f32; gamepad::AXIS_COUNT],
+    deadzone:     f32,
+
+    bindings:    Bindings,
+    cursor_xy:   (i32, i32),
+    window_size: (u32, u32),
+    wheel_delta: (f32, f32),
+    text:        TextBuffer,
+    focused:     bool,
+}
+
+impl Input {
+	pub fn new() -> Input {
+		Input{
+			pressed_keys:   BitSet::new(),
+			released_keys:  BitSet::new(),
+			held_keys:      BitSet::new(),
+
+            pressed_buttons:  BitSet::new(),
+            released_buttons: BitSet::new(),
+            held_buttons:     BitSet::new(),
+
+            pressed_pad:  BitSet::new(),
+            released_pad: BitSet::new(),
+            held_pad:     BitSet::new(),
+
+            key_times:    PressTimes::new(),
+            button_times: PressTimes::new(),
+            pad_times:    PressTimes::new(),
+            axis_times:   PressTimes::new(),
+            tick:         0,
+
+            axes:         [0.0; gamepad::AXIS_COUNT],
+            prev_axes:    [0.0; gamepad::AXIS_COUNT],
+            deadzone:     DEFAULT_DEADZONE,
+
+            bindings:    Bindings::default(),
+            cursor_xy:   (0,0),
+            window_size: DEFAULT_VIEWPORT,
+            wheel_delta: (0.0, 0.0),
+            text:        TextBuffer::new(),
+            focused:     true,
+		}
+	}
+
+    /// Replaces the bindings used to answer queries about actions.
+    pub fn set_bindings(&mut self, bindings: Bindings) { self.bindings = bindings; }
+
+    /// The bindings used to answer queries about actions.
+    pub fn bindings(&self) -> &Bindings { &self.bindings }
+
+    /// How far a stick must move from center before it registers.
+    pub fn deadzone(&self) -> f32 { self.deadzone }
+
+    /// Sets how far a stick must move from center before it registers.
+    /// (This is clamped between 0.0 and `MAX_DEADZONE`.)
+    pub fn set_deadzone(&mut self, deadzone: f32) { self.deadzone = deadzone.max(0.0).min(MAX_DEADZONE); }
+
+	/// Feeds a single event into the buffer.
+	pub fn apply(&mut self, ev: InputEvent) {
+		match ev {
+			InputEvent::KeyDown(key)     => self.key_down_event(key),
+			InputEvent::KeyUp(key)       => self.key_up_event(key),
+			InputEvent::ButtonDown(btn)  => self.button_down_event(btn),
+			InputEvent::ButtonUp(btn)    => self.button_up_event(btn),
+			InputEvent::CursorMoved(x,y) => self.move_cursor(x, y),
+			InputEvent::Wheel(dx,dy)     => self.scroll_wheel(dx, dy),
+            InputEvent::PadDown(btn)     => self.pad_down_event(btn),
+            InputEvent::PadUp(btn)       => self.pad_up_event(btn),
+            InputEvent::PadAxis(axis,v)  => self.move_axis(axis, v),
+            InputEvent::Char(ch)         => self.text.push(ch),
+            InputEvent::Focus(focused)   => self.focus_event(focused),
+            InputEvent::Resized(w,h)     => self.window_size = (w, h),
+		}
+	}
+
+	/// Resets the toggle states of pressed & released keys & buttons,
+	/// along w/ the distance scrolled by the mouse wheel. This also
+	/// advances the step counter used to time holds & double taps.
+	pub fn begin_new_frame(&mut self) {
+		self.tick += 1;
+		self.pressed_keys.clear();
+		self.released_keys.clear();
+		self.pressed_buttons.clear();
+		self.released_buttons.clear();
+		self.pressed_pad.clear();
+		self.released_pad.clear();
+		self.prev_axes = self.axes;
+		self.wheel_delta = (0.0, 0.0);
+	}
+
+    /// Handles a mouse movement event
+    pub fn move_cursor(&mut self, x: i32, y: i32) { self.cursor_xy = (x,y) }
+
+    /// Handles a mouse wheel event, measured in lines (y grows away from the user)
+    pub fn scroll_wheel(&mut self, dx: f32, dy: f32) {
+        self.wheel_delta.0 += dx;
+        self.wheel_delta.1 += dy;
+    }
+
+	/// Handles a key down event
+	pub fn key_down_event(&mut self, key: Key) {
+		// the OS repeats key down events while a key is held
+		if !self.held_keys.contains(key) { self.key_times.press(key, self.tick); }
+		self.pressed_keys.insert(key);
+		self.held_keys.insert(key);
+	}
+
+	/// Handles a key up event
+	pub fn key_up_event(&mut self, key: Key) {
+		self.released_keys.insert(key);
+		self.held_keys.remove(key);
+	}
+
+    /// Handles a mouse button down event
+    pub fn button_down_event(&mut self, button: MouseButton) {
+        if !self.held_buttons.contains(button) { self.button_times.press(button, self.tick); }
+        self.pressed_buttons.insert(button);
+        self.held_buttons.insert(button);
+    }
+
+    /// Handles a mouse button up event
+    pub fn button_up_event(&mut self, button: MouseButton) {
+        self.released_buttons.insert(button);
+        self.held_buttons.remove(button);
+    }
+
+    /// Handles a gamepad button down event
+    pub fn pad_down_event(&mut self, button: GamepadButton) {
+        if !self.held_pad.contains(button) { self.pad_times.press(button, self.tick); }
+        self.pressed_pad.insert(button);
+        self.held_pad.insert(button);
+    }
+
+    /// Handles a gamepad button up event
+    pub fn pad_up_event(&mut self, button: GamepadButton) {
+        self.released_pad.insert(button);
+        self.held_pad.remove(button);
+    }
+
+    /// Handles a gamepad axis event, storing the raw position of the axis.
+    /// Pushing either half of the axis past the halfway point counts as a
+    /// press of that half, for timing holds & double taps.
+    pub fn move_axis(&mut self, axis: Axis, value: f32) {
+        let before = self.axes;
+        self.axes[axis as usize] = value.max(-1.0).min(1.0);
+
+        for &dir in &[AxisDir::Positive, AxisDir::Negative] {
+            if self.axis_toward(&before, axis, dir) < AXIS_PRESS_THRESHOLD &&
+               self.axis_toward(&self.axes, axis, dir) >= AXIS_PRESS_THRESHOLD {
+                self.axis_times.press((axis, dir), self.tick);
+            }
+        }
+    }
+
+    /// Handles the window gaining or losing focus. Losing focus releases
+    /// every held key & button, as if the player had let go of them, and
+    /// recenters all gamepad axes.
+    pub fn focus_event(&mut self, focused: bool) {
+        self.focused = focused;
+        if focused { return }
+
+        for key in self.held_keys.iter().filter_map(Key::from_bit_index) {
+            self.key_up_event(key);
+        }
+
+        for button in self.held_buttons.iter().filter_map(MouseButton::from_bit_index) {
+            self.button_up_event(button);
+        }
+
+        for button in self.held_pad.iter().filter_map(GamepadButton::from_bit_index) {
+            self.pad_up_event(button);
+        }
+
+        self.axes = [0.0; gamepad::AXIS_COUNT];
+    }
+
+    /// Responds true unless the window is in the background.
+    pub fn is_focused(&self) -> bool { self.focused }
+
+    /// Starts collecting typed characters, see `text_line()` & `take_text_line()`.
+    pub fn begin_text_input(&mut self) { self.text.set_active(true) }
+
+    /// Stops collecting typed characters, any partial line is discarded.
+    pub fn end_text_input(&mut self) { self.text.set_active(false) }
+
+    /// Responds true while typed characters are being collected.
+    pub fn is_text_input(&self) -> bool { self.text.is_active() }
+
+    /// Fetches the line of text currently being typed.
+    pub fn text_line(&self) -> &str { self.text.line() }
+
+    /// Fetches the oldest line of text submitted w/ enter, if any.
+    pub fn take_text_line(&mut self) -> Option<String> { self.text.take_line() }
+
+    /// Fetches coordinate of mouse cursor in screen space
+    #[allow(dead_code)]
+    pub fn get_cursor(&self) -> (i32, i32) { self.cursor_xy }
+
+    /// Fetches size of the window in pixels
+    pub fn get_window_size(&self) -> (u32, u32) { self.window_size }
+
+    /// Fetches distance (in lines) scrolled since last call to `beginNewFrame()`
+    pub fn get_wheel(&self) -> (f32, f32) { self.wheel_delta }
+
+	/// Responds true if key was pressed since last call to `beginNewFrame()`.
+	/// Responds false otherwise.
+	pub fn was_key_pressed(&self, key: Key) -> bool {
+		self.pressed_keys.contains(key)
+	}
+	
+	/// Responds true if key was released since last call to `beginNewFrame()`.
+	/// Responds false otherwise.
+	#[allow(dead_code)]
+	pub fn was_key_released(&self, key: Key) -> bool {
+		self.released_keys.contains(key)
+	}
+	
+	/// Responds true if key has been pressed since last call to `beginNewFrame()`
+	/// but _has not yet been released._
+	///
+	/// Responds false otherwise.
+	pub fn is_key_held(&self, key: Key) -> bool {
+		self.held_keys.contains(key)
+	}
+
+    /// Responds true if mouse button was pressed since last call to `beginNewFrame()`.
+    pub fn was_button_pressed(&self, button: MouseButton) -> bool {
+        self.pressed_buttons.contains(button)
+    }
+
+    /// Responds true if mouse button was released since last call to `beginNewFrame()`.
+    #[allow(dead_code)]
+    pub fn was_button_released(&self, button: MouseButton) -> bool {
+        self.released_buttons.contains(button)
+    }
+
+    /// Responds true if mouse button has been pressed but _has not yet been released._
+    pub fn is_button_held(&self, button: MouseButton) -> bool {
+        self.held_buttons.contains(button)
+    }
+
+    /// Responds true if gamepad button was pressed since last call to `beginNewFrame()`.
+    pub fn was_pad_pressed(&self, button: GamepadButton) -> bool {
+        self.pressed_pad.contains(button)
+    }
+
+    /// Responds true if gamepad button was released since last call to `beginNewFrame()`.
+    #[allow(dead_code)]
+    pub fn was_pad_released(&self, button: GamepadButton) -> bool {
+        self.released_pad.contains(button)
+    }
+
+    /// Responds true if gamepad button has been pressed but _has not yet been released._
+    pub fn is_pad_held(&self, button: GamepadButton) -> bool {
+        self.held_pad.contains(button)
+    }
+
+    /// Responds w/ the number of steps `key` has been held for, counting
+    /// the step in which it was pressed. Responds 0 if it is not held.
+    pub fn key_held_ticks(&self, key: Key) -> u64 {
+        if !self.is_key_held(key) { return 0 }
+        held_ticks(self.key_times.last_press(key), self.tick)
+    }
+
+    /// Responds true if `key` was pressed since last call to `beginNewFrame()`
+    /// and its previous press was no more than `window` steps earlier.
+    #[allow(dead_code)]
+    pub fn was_key_double_tapped(&self, key: Key, window: u64) -> bool {
+        self.was_key_pressed(key) &&
+            is_double_tap(self.key_times.last_press(key), self.key_times.previous_press(key), window)
+    }
+
+    /// Responds true if every key in `keys` is currently held down.
+    pub fn is_chord_held(&self, keys: &[Key]) -> bool {
+        !keys.is_empty() && keys.iter().all(|&key| self.is_key_held(key))
+    }
+
+    /// Responds true if every key in `keys` is held, and the last of them
+    /// was pressed since last call to `beginNewFrame()`.
+    pub fn was_chord_pressed(&self, keys: &[Key]) -> bool {
+        self.is_chord_held(keys) && keys.iter().any(|&key| self.was_key_pressed(key))
+    }
+
+    /// Fetches the position of a gamepad axis w/ the deadzone applied.
+    #[allow(dead_code)]
+    pub fn axis(&self, axis: Axis) -> f32 {
+        gamepad::apply_deadzone(self.axes[axis as usize], self.deadzone)
+    }
+
+    // how far an axis is pushed in `dir`, zero if pushed the other way
+    fn axis_toward(&self, axes: &[f32], axis: Axis, dir: AxisDir) -> f32 {
+        let value = gamepad::apply_deadzone(axes[axis as usize], self.deadzone);
+        match dir {
+            AxisDir::Positive => value.max(0.0),
+            AxisDir::Negative => (-value).max(0.0),
+        }
+    }
+
+    // keyboard bindings are ignored while the player is typing
+    fn is_suppressed(&self, binding: Binding) -> bool {
+        match binding {
+            Binding::Key(_) | Binding::Chord(..) => self.text.is_active(),
+            _ => false,
+        }
+    }
+
+    fn binding_pressed(&self, binding: Binding) -> bool {
+        if self.is_suppressed(binding) { return false }
+
+        match binding {
+            Binding::Key(key)      => self.was_key_pressed(key),
+            Binding::Mouse(button) => self.was_button_pressed(button),
+            Binding::Pad(button)   => self.was_pad_pressed(button),
+            Binding::Chord(a, b)   => self.was_chord_pressed(&[a, b]),
+            Binding::Axis(axis, dir) => {
+                self.axis_toward(&self.prev_axes, axis, dir) < AXIS_PRESS_THRESHOLD &&
+                self.axis_toward(&self.axes, axis, dir) >= AXIS_PRESS_THRESHOLD
+            },
+        }
+    }
+
+    fn binding_released(&self, binding: Binding) -> bool {
+        if self.is_suppressed(binding) { return false }
+
+        match binding {
+            Binding::Key(key)      => self.was_key_released(key),
+            Binding::Mouse(button) => self.was_button_released(button),
+            Binding::Pad(button)   => self.was_pad_released(button),
+            Binding::Chord(a, b)   => {
+                // one key let go while the other was still down, or both at once
+                (self.was_key_released(a) || self.was_key_released(b)) &&
+                (self.is_key_held(a) || self.is_key_held(b) || (self.was_key_released(a) && self.was_key_released(b)))
+            },
+            Binding::Axis(axis, dir) => {
+                self.axis_toward(&self.prev_axes, axis, dir) >= AXIS_PRESS_THRESHOLD &&
+                self.axis_toward(&self.axes, axis, dir) < AXIS_PRESS_THRESHOLD
+            },
+        }
+    }
+
+    fn binding_held(&self, binding: Binding) -> bool {
+        if self.is_suppressed(binding) { return false }
+
+        match binding {
+            Binding::Key(key)      => self.is_key_held(key),
+            Binding::Mouse(button) => self.is_button_held(button),
+            Binding::Pad(button)   => self.is_pad_held(button),
+            Binding::Chord(a, b)   => self.is_chord_held(&[a, b]),
+            Binding::Axis(axis, dir) => self.axis_toward(&self.axes, axis, dir) >= AXIS_PRESS_THRESHOLD,
+        }
+    }
+
+    fn binding_held_ticks(&self, binding: Binding) -> u64 {
+        if !self.binding_held(binding) { return 0 }
+
+        match binding {
+            Binding::Key(key)      => held_ticks(self.key_times.last_press(key), self.tick),
+            Binding::Mouse(button) => held_ticks(self.button_times.last_press(button), self.tick),
+            Binding::Pad(button)   => held_ticks(self.pad_times.last_press(button), self.tick),
+            Binding::Chord(a, b)   => u64::min(self.key_held_ticks(a), self.key_held_ticks(b)),
+            Binding::Axis(axis, dir) => held_ticks(self.axis_times.last_press((axis, dir)), self.tick),
+        }
+    }
+
+    fn binding_double_tapped(&self, binding: Binding, window: u64) -> bool {
+        if !self.binding_pressed(binding) { return false }
+
+        let times = match binding {
+            Binding::Key(key)      => (self.key_times.last_press(key), self.key_times.previous_press(key)),
+            Binding::Mouse(button) => (self.button_times.last_press(button), self.button_times.previous_press(button)),
+            Binding::Pad(button)   => (self.pad_times.last_press(button), self.pad_times.previous_press(button)),
+            Binding::Axis(axis, dir) => (self.axis_times.last_press((axis, dir)), self.axis_times.previous_press((axis, dir))),
+            Binding::Chord(..) => return false,
+        };
+
+        is_double_tap(times.0, times.1, window)
+    }
+
+    /// Responds true if any binding for `action` was pressed since last
+    /// call to `beginNewFrame()`. Axes count as pressed when they cross
+    /// the halfway point.
+    pub fn was_action_pressed(&self, action: Action) -> bool {
+        self.bindings.get(action).iter().any(|&binding| self.binding_pressed(binding))
+    }
+
+    /// Responds true if any binding for `action` was released since last
+    /// call to `beginNewFrame()`.
+    #[allow(dead_code)]
+    pub fn was_action_released(&self, action: Action) -> bool {
+        self.bindings.get(action).iter().any(|&binding| self.binding_released(binding))
+    }
+
+    /// Responds true if any binding for `action` is currently held down.
+    #[allow(dead_code)]
+    pub fn is_action_held(&self, action: Action) -> bool {
+        self.bindings.get(action).iter().any(|&binding| self.binding_held(binding))
+    }
+
+    /// Responds w/ the number of steps `action` has been held for, counting
+    /// the step in which it was pressed. If several bindings are held the
+    /// longest hold wins, axes are timed from when they crossed the halfway point.
+    pub fn action_held_ticks(&self, action: Action) -> u64 {
+        self.bindings.get(action).iter()
+            .map(|&binding| self.binding_held_ticks(binding))
+            .max().unwrap_or(0)
+    }
+
+    /// Responds true if a key, button or axis bound to `action` was pressed since
+    /// last call to `beginNewFrame()`, and had also been pressed no more than
+    /// `window` steps before that.
+    #[allow(dead_code)]
+    pub fn was_action_double_tapped(&self, action: Action, window: u64) -> bool {
+        self.bindings.get(action).iter().any(|&binding| self.binding_double_tapped(binding, window))
+    }
+
+    /// Responds w/ how strongly `action` is being requested, from 0.0 to 1.0.
+    /// Keys & buttons are all or nothing, axes report how far they are pushed.
+    /// If several bindings are active the strongest one wins.
+    pub fn action_value(&self, action: Action) -> f32 {
+        self.bindings.get(action).iter().fold(0.0, |strongest, &binding| {
+            let value = match binding {
+                Binding::Axis(axis, dir) => self.axis_toward(&self.axes, axis, dir),
+                _ => if self.binding_held(binding) { 1.0 } else { 0.0 },
+            };
+
+            f32::max(strongest, value)
+        })
+    }
+}
+
+fn held_ticks(pressed_at: Option<u64>, now: u64) -> u64 {
+    pressed_at.map_or(0, |tick| now - tick + 1)
+}
+
+fn is_double_tap(last: Option<u64>, previous: Option<u64>, window: u64) -> bool {
+    match (last, previous) {
+        (Some(last), Some(previous)) => last - previous <= window,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Action, Axis, Input, InputEvent};
+
+    #[test]
+    fn times_axis_holds() {
+        let mut controller = Input::new();
+        controller.apply(InputEvent::PadAxis(Axis::LeftY, 1.0));
+        assert_eq!(controller.action_held_ticks(Action::Thrust), 1);
+
+        // wobbling about while still past halfway is not a new press
+        controller.begin_new_frame();
+        controller.apply(InputEvent::PadAxis(Axis::LeftY, 0.8));
+        controller.begin_new_frame();
+        assert_eq!(controller.action_held_ticks(Action::Thrust), 3);
+
+        controller.apply(InputEvent::PadAxis(Axis::LeftY, 0.0));
+        assert_eq!(controller.action_held_ticks(Action::Thrust), 0);
+
+        controller.begin_new_frame();
+        controller.apply(InputEvent::PadAxis(Axis::LeftY, 1.0));
+        assert_eq!(controller.action_held_ticks(Action::Thrust), 1);
+        assert!(controller.was_action_double_tapped(Action::Thrust, 3));
+    }
+}
@@ -0,0 +1,42 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::time::Duration;
+
+use input::{Input, InputEvent};
+use input::script::{duration_to_ns, format_event};
+
+/// Writes every input event fed to the game into a script file, tagged
+/// with the simulation tick it arrived before. Along with the timestep,
+/// world seed, and the window size, bindings & deadzone of the `Input`
+/// being recorded,
+/// this is enough for `InputScript` to reproduce the session exactly.
+pub struct InputRecorder {
+    out: BufWriter<File>,
+}
+
+impl InputRecorder {
+    pub fn create(path: &str, timestep: Duration, seed: [u32; 4], controller: &Input) -> io::Result<Self> {
+        let mut out = BufWriter::new(File::create(path)?);
+
+        writeln!(out, "# megumin input recording")?;
+        writeln!(out, "timestep {}", duration_to_ns(timestep))?;
+        writeln!(out, "seed {} {} {} {}", seed[0], seed[1], seed[2], seed[3])?;
+        let (w, h) = controller.get_window_size();
+        writeln!(out, "viewport {} {}", w, h)?;
+        writeln!(out, "deadzone {}", controller.deadzone())?;
+        for line in controller.bindings().lines() { writeln!(out, "bind {}", line)?; }
+
+        Ok(InputRecorder { out: out })
+    }
+
+    /// Records an event which will be seen by simulation step `tick`.
+    pub fn record(&mut self, tick: u64, ev: InputEvent) -> io::Result<()> {
+        writeln!(self.out, "{} {}", tick, format_event(ev))
+    }
+
+    /// Marks the tick at which the session ended and flushes the file.
+    pub fn finish(mut self, tick: u64) -> io::Result<()> {
+        writeln!(self.out, "end {}", tick)?;
+        self.out.flush()
+    }
+}
@@ -0,0 +1,363 @@
+use std::char;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::time::Duration;
+
+use input::{Binding, Bindings, Input, InputEvent};
+use input::gamepad::Axis;
+
+/// An InputScript replays a fixed list of input events into an `Input`
+/// buffer, each event is scheduled to arrive just before a given tick of
+/// the simulation runs. This lets the game be driven without a window,
+/// or a recorded session (see `InputRecorder`) to be played back.
+///
+/// Scripts are plain text, one event per line:
+///
+/// ```text
+/// # tick  action  args
+///   0     down    W
+///   0     cursor  640 360
+///   60    down    MouseLeft
+///   61    up      MouseLeft
+///   90    wheel   0 -1.5
+///   100   axis    LeftY 0.75
+///   110   down    PadSouth
+///   130   char    97      # unicode code point
+///   140   focus   off
+///   150   resize  1920 1080
+///   120   up      W
+///   121   down    S
+/// ```
+///
+/// A script may optionally pin the simulation settings it was recorded
+/// with, and the tick at which the session ended:
+///
+/// ```text
+/// timestep 8333333        # nanoseconds per step
+/// seed 157 27 24 133      # world RNG seed
+/// viewport 1280 720       # window size when recording began
+/// deadzone 0.2            # gamepad stick deadzone
+/// bind thrust = W, LeftY+ # as in a bindings file, one action per line
+/// end 480
+/// ```
+///
+/// Resizes (including `viewport`) only change the window size the simulation
+/// sees, so a replay simulates the same view regardless of the window it's
+/// shown in. Actions a script doesn't bind keep their default bindings, but if it
+/// binds none at all the bindings already in use are left alone.
+///
+/// Blank lines and anything following a `#` are ignored.
+pub struct InputScript {
+    timestep: Option<Duration>,
+    seed:     Option<[u32; 4]>,
+    viewport: Option<(u32, u32)>,
+    deadzone: Option<f32>,
+    bindings: Option<Bindings>,
+    end:      Option<u64>,
+
+    events: Vec<(u64, InputEvent)>,
+    cursor: usize,
+}
+
+impl InputScript {
+    pub fn new() -> Self {
+        InputScript {
+            timestep: None,
+            seed:     None,
+            viewport: None,
+            deadzone: None,
+            bindings: None,
+            end:      None,
+
+            events: vec![],
+            cursor: 0,
+        }
+    }
+
+    /// Reads a script from disk, see `InputScript` for the file format.
+    pub fn load(path: &str) -> io::Result<Self> {
+        let file = File::open(path)?;
+        let mut script = InputScript::new();
+
+        for (line_no, line) in BufReader::new(file).lines().enumerate() {
+            let line = line?;
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() { continue }
+
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if script.parse_fields(&fields).is_none() {
+                return Err(io::Error::new(io::ErrorKind::InvalidData,
+                                          format!("{}:{}: bad script line `{}`", path, line_no + 1, line)));
+            }
+        }
+
+        Ok(script)
+    }
+
+    /// Length of a simulation step this script expects, if it specifies one.
+    pub fn timestep(&self) -> Option<Duration> { self.timestep }
+
+    /// The world seed this script expects, if it specifies one.
+    pub fn seed(&self) -> Option<[u32; 4]> { self.seed }
+
+    /// Installs the viewport, bindings & deadzone this script was recorded
+    /// with, if it specifies them, in place of whatever `controller` was set
+    /// up with.
+    pub fn configure(&self, controller: &mut Input) {
+        if let Some((w, h)) = self.viewport { controller.apply(InputEvent::Resized(w, h)); }
+        if let Some(ref bindings) = self.bindings { controller.set_bindings(bindings.clone()); }
+        if let Some(deadzone) = self.deadzone { controller.set_deadzone(deadzone); }
+    }
+
+    /// The tick at which the recorded session ended, if known.
+    pub fn end(&self) -> Option<u64> { self.end }
+
+    /// Schedules `ev` to arrive before simulation step `tick`.
+    pub fn push(&mut self, tick: u64, ev: InputEvent) {
+        // keep the list sorted, events on the same tick stay in file order
+        let idx = self.events.iter().position(|&(t, _)| t > tick).unwrap_or(self.events.len());
+        self.events.insert(idx, (tick, ev));
+    }
+
+    /// Feeds every event scheduled up to & including `tick` into the input
+    /// buffer. Ticks are expected to be applied in increasing order.
+    pub fn apply(&mut self, tick: u64, controller: &mut Input) {
+        while self.cursor < self.events.len() && self.events[self.cursor].0 <= tick {
+            controller.apply(self.events[self.cursor].1);
+            self.cursor += 1;
+        }
+    }
+
+    fn parse_fields(&mut self, fields: &[&str]) -> Option<()> {
+        match (fields[0], fields.len()) {
+            ("timestep", 2) => {
+                let ns = fields[1].parse().ok()?;
+                self.timestep = Some(ns_to_duration(ns));
+            },
+
+            ("seed", 5) => {
+                let mut seed = [0; 4];
+                for (word, field) in seed.iter_mut().zip(&fields[1..]) {
+                    *word = field.parse().ok()?;
+                }
+
+                // the world's xorshift generator can't be seeded w/ all zeroes
+                if seed == [0; 4] { return None }
+
+                self.seed = Some(seed);
+            },
+
+            ("viewport", 3) => {
+                let w = fields[1].parse().ok()?;
+                let h = fields[2].parse().ok()?;
+                self.viewport = Some((w, h));
+            },
+
+            ("deadzone", 2) => {
+                self.deadzone = Some(fields[1].parse().ok()?);
+            },
+
+            ("bind", _) => {
+                let line = fields[1..].join(" ");
+                self.bindings.get_or_insert_with(Bindings::default).parse_line(&line)?;
+            },
+
+            ("end", 2) => {
+                self.end = Some(fields[1].parse().ok()?);
+            },
+
+            (tick, _) => {
+                let tick = tick.parse().ok()?;
+                let ev   = parse_event(&fields[1..])?;
+                self.push(tick, ev);
+            },
+        }
+
+        Some(())
+    }
+}
+
+fn parse_event(args: &[&str]) -> Option<InputEvent> {
+    match (args.get(0).cloned(), args.len()) {
+        (Some("down"), 2) => match Binding::from_name(args[1])? {
+            Binding::Key(key)      => Some(InputEvent::KeyDown(key)),
+            Binding::Mouse(button) => Some(InputEvent::ButtonDown(button)),
+            Binding::Pad(button)   => Some(InputEvent::PadDown(button)),
+            Binding::Axis(..) | Binding::Chord(..) => None,
+        },
+
+        (Some("up"), 2) => match Binding::from_name(args[1])? {
+            Binding::Key(key)      => Some(InputEvent::KeyUp(key)),
+            Binding::Mouse(button) => Some(InputEvent::ButtonUp(button)),
+            Binding::Pad(button)   => Some(InputEvent::PadUp(button)),
+            Binding::Axis(..) | Binding::Chord(..) => None,
+        },
+
+        (Some("cursor"), 3) => {
+            let x = args[1].parse().ok()?;
+            let y = args[2].parse().ok()?;
+            Some(InputEvent::CursorMoved(x, y))
+        },
+
+        (Some("wheel"), 3) => {
+            let dx = args[1].parse().ok()?;
+            let dy = args[2].parse().ok()?;
+            Some(InputEvent::Wheel(dx, dy))
+        },
+
+        // characters are stored as code points, so whitespace survives
+        (Some("char"), 2) => {
+            let code = args[1].parse().ok()?;
+            Some(InputEvent::Char(char::from_u32(code)?))
+        },
+
+        (Some("focus"), 2) => match args[1] {
+            "on"  => Some(InputEvent::Focus(true)),
+            "off" => Some(InputEvent::Focus(false)),
+            _     => None,
+        },
+
+        (Some("resize"), 3) => {
+            let w = args[1].parse().ok()?;
+            let h = args[2].parse().ok()?;
+            Some(InputEvent::Resized(w, h))
+        },
+
+        (Some("axis"), 3) => {
+            let axis  = Axis::from_name(args[1])?;
+            let value = args[2].parse().ok()?;
+            Some(InputEvent::PadAxis(axis, value))
+        },
+
+        _ => None,
+    }
+}
+
+/// Total length of `dt` in nanoseconds, as written after `timestep`.
+pub fn duration_to_ns(dt: Duration) -> u64 {
+    dt.as_secs() * 1_000_000_000 + dt.subsec_nanos() as u64
+}
+
+/// Rebuilds a `Duration` from a count of nanoseconds.
+pub fn ns_to_duration(ns: u64) -> Duration {
+    Duration::new(ns / 1_000_000_000, (ns % 1_000_000_000) as u32)
+}
+
+/// Formats an event as it would appear in a script, following the tick.
+pub fn format_event(ev: InputEvent) -> String {
+    match ev {
+        InputEvent::KeyDown(key) => format!("down {}", key.name()),
+        InputEvent::KeyUp(key)   => format!("up {}", key.name()),
+        InputEvent::ButtonDown(button) => format!("down {}", button.name()),
+        InputEvent::ButtonUp(button)   => format!("up {}", button.name()),
+        InputEvent::CursorMoved(x, y) => format!("cursor {} {}", x, y),
+        InputEvent::Wheel(dx, dy)     => format!("wheel {} {}", dx, dy),
+        InputEvent::PadDown(button) => format!("down {}", button.name()),
+        InputEvent::PadUp(button)   => format!("up {}", button.name()),
+        InputEvent::PadAxis(axis, value) => format!("axis {} {}", axis.name(), value),
+        InputEvent::Char(ch) => format!("char {}", ch as u32),
+        InputEvent::Focus(focused) => format!("focus {}", if focused { "on" } else { "off" }),
+        InputEvent::Resized(w, h)  => format!("resize {} {}", w, h),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::time::Duration;
+
+    use input::{Action, Binding, Bindings, Input, InputEvent, InputRecorder, Key};
+    use super::{format_event, InputScript};
+
+    fn parse(lines: &[&str]) -> Option<InputScript> {
+        let mut script = InputScript::new();
+        for line in lines {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            script.parse_fields(&fields)?;
+        }
+
+        Some(script)
+    }
+
+    fn formatted(script: &InputScript) -> Vec<String> {
+        script.events.iter().map(|&(tick, ev)| format!("{} {}", tick, format_event(ev))).collect()
+    }
+
+    #[test]
+    fn parses_settings_and_events() {
+        let script = parse(&["timestep 8333333", "seed 1 2 3 4", "end 480",
+                             "0 down W", "120 up W", "60 cursor 640 360"]).unwrap();
+
+        assert_eq!(script.timestep(), Some(Duration::new(0, 8333333)));
+        assert_eq!(script.seed(), Some([1, 2, 3, 4]));
+        assert_eq!(script.end(), Some(480));
+        assert_eq!(formatted(&script), vec!["0 down W", "60 cursor 640 360", "120 up W"]);
+    }
+
+    #[test]
+    fn parses_timesteps_longer_than_a_second() {
+        let script = parse(&["timestep 2500000000"]).unwrap();
+        assert_eq!(script.timestep(), Some(Duration::new(2, 500_000_000)));
+    }
+
+    #[test]
+    fn rejects_bad_lines() {
+        assert!(parse(&["seed 0 0 0 0"]).is_none());
+        assert!(parse(&["seed 1 2 3"]).is_none());
+        assert!(parse(&["0 down NotAKey"]).is_none());
+        assert!(parse(&["0 squish W"]).is_none());
+        assert!(parse(&["soon down W"]).is_none());
+    }
+
+    #[test]
+    fn replays_what_was_recorded() {
+        let path = env::temp_dir().join("megumin-record-test.txt");
+        let path = path.to_str().unwrap();
+        let step = Duration::new(1, 250);
+
+        let mut bindings = Bindings::empty();
+        bindings.bind(Action::Thrust, Binding::Key(Key::Up));
+        bindings.bind(Action::Fire, Binding::Chord(Key::LShift, Key::Space));
+
+        let mut live = Input::new();
+        live.set_bindings(bindings.clone());
+        live.set_deadzone(0.35);
+        live.apply(InputEvent::Resized(800, 600));
+
+        let mut recorder = InputRecorder::create(path, step, [9, 8, 7, 6], &live).unwrap();
+        recorder.record(0, InputEvent::KeyDown(Key::W)).unwrap();
+        recorder.record(3, InputEvent::CursorMoved(-4, 12)).unwrap();
+        recorder.record(5, InputEvent::Char(' ')).unwrap();
+        recorder.record(7, InputEvent::KeyUp(Key::W)).unwrap();
+        recorder.finish(10).unwrap();
+
+        let script = InputScript::load(path).unwrap();
+        assert_eq!(script.timestep(), Some(step));
+        assert_eq!(script.seed(), Some([9, 8, 7, 6]));
+        assert_eq!(script.end(), Some(10));
+        assert_eq!(formatted(&script), vec!["0 down W", "3 cursor -4 12", "5 char 32", "7 up W"]);
+
+        // the replay uses the recorded bindings, not those it starts w/
+        let mut replay = Input::new();
+        script.configure(&mut replay);
+        assert_eq!(replay.bindings().lines(), bindings.lines());
+        assert_eq!(replay.deadzone(), 0.35);
+        assert_eq!(replay.get_window_size(), (800, 600));
+    }
+
+    #[test]
+    fn binds_over_the_defaults() {
+        let script = parse(&["bind fire = MouseRight, RightX-", "bind thrust ="]).unwrap();
+        let mut controller = Input::new();
+        script.configure(&mut controller);
+
+        let bindings = controller.bindings();
+        assert_eq!(bindings.get(Action::Fire), &[Binding::from_name("MouseRight").unwrap(),
+                                                 Binding::from_name("RightX-").unwrap()]);
+        assert_eq!(bindings.get(Action::Thrust), &[]);
+        assert_eq!(bindings.get(Action::StrafeLeft), Bindings::default().get(Action::StrafeLeft));
+
+        assert!(parse(&["bind thrust = NotAKey"]).is_none());
+        assert!(parse(&["bind launch = W"]).is_none());
+    }
+}
@@ -0,0 +1,85 @@
+use std::collections::VecDeque;
+
+// buffers start out w/ room for a typical console command
+static TEXT_CAPACITY: usize = 128;
+
+/// Collects characters typed while `Input` is in text mode.
+///
+/// Characters arrive already composed by the window system (including
+/// input from an IME), so the buffer only ever deals in whole `char`s and
+/// never splits a multi-byte sequence. Backspace removes the last `char`,
+/// while enter moves the line into a queue of submitted lines.
+pub struct TextBuffer {
+    active:    bool,
+    line:      String,
+    submitted: VecDeque<String>,
+}
+
+impl TextBuffer {
+    pub fn new() -> Self {
+        TextBuffer {
+            active:    false,
+            line:      String::with_capacity(TEXT_CAPACITY),
+            submitted: VecDeque::new(),
+        }
+    }
+
+    pub fn is_active(&self) -> bool { self.active }
+
+    /// Starts (or stops) collecting typed characters, stopping throws
+    /// away any partially typed line.
+    pub fn set_active(&mut self, active: bool) {
+        self.active = active;
+        if !active { self.line.clear(); }
+    }
+
+    /// Handles a single typed character, ignored unless the buffer is active.
+    pub fn push(&mut self, ch: char) {
+        if !self.active { return }
+
+        match ch {
+            '\u{8}' | '\u{7f}' => { self.line.pop(); },
+            '\r' | '\n' => {
+                let line = self.line.clone();
+                self.submitted.push_back(line);
+                self.line.clear();
+            },
+
+            // tab, escape, etc. are left for key bindings to handle
+            ch if ch.is_control() => (),
+            ch => self.line.push(ch),
+        }
+    }
+
+    /// The line currently being typed.
+    pub fn line(&self) -> &str { &self.line }
+
+    /// Removes the oldest line submitted w/ enter, if any.
+    pub fn take_line(&mut self) -> Option<String> {
+        self.submitted.pop_front()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TextBuffer;
+
+    fn type_str(buffer: &mut TextBuffer, text: &str) {
+        for ch in text.chars() { buffer.push(ch) }
+    }
+
+    #[test]
+    fn submits_lines_in_order() {
+        let mut buffer = TextBuffer::new();
+        type_str(&mut buffer, "ignored");
+        assert_eq!(buffer.line(), "");
+
+        buffer.set_active(true);
+        type_str(&mut buffer, "firsx\u{8}t\nsecond\nthi");
+
+        assert_eq!(buffer.take_line(), Some("first".to_string()));
+        assert_eq!(buffer.take_line(), Some("second".to_string()));
+        assert_eq!(buffer.take_line(), None);
+        assert_eq!(buffer.line(), "thi");
+    }
+}
@@ -0,0 +1,37 @@
+use input::bitset::{BitIndex, BITSET_BITS};
+
+/// Remembers the step at which each key (or button, or axis) was last pressed,
+/// along w/ the press before that, so holds & double taps can be timed.
+///
+/// Times are counted in simulation steps rather than wall clock time,
+/// this keeps them deterministic when input is replayed from a script.
+pub struct PressTimes {
+    last:     [Option<u64>; BITSET_BITS],
+    previous: [Option<u64>; BITSET_BITS],
+}
+
+impl PressTimes {
+    pub fn new() -> Self {
+        PressTimes {
+            last:     [None; BITSET_BITS],
+            previous: [None; BITSET_BITS],
+        }
+    }
+
+    /// Records that `item` was pressed during step `tick`.
+    pub fn press<T: BitIndex>(&mut self, item: T, tick: u64) {
+        let idx = item.bit_index();
+        self.previous[idx] = self.last[idx];
+        self.last[idx] = Some(tick);
+    }
+
+    /// The step at which `item` was most recently pressed.
+    pub fn last_press<T: BitIndex>(&self, item: T) -> Option<u64> {
+        self.last[item.bit_index()]
+    }
+
+    /// The step at which `item` was pressed before its most recent press.
+    pub fn previous_press<T: BitIndex>(&self, item: T) -> Option<u64> {
+        self.previous[item.bit_index()]
+    }
+}
@@ -2,30 +2,109 @@
 extern crate image;
 extern crate rand;
 extern crate rusttype;
+#[cfg(feature = "gamepad")] extern crate gilrs;
 
-#[allow(dead_code)] mod input;
-#[allow(dead_code)] mod units;
+mod camera;
+mod clock;
+mod console;
 mod entities;
+mod error;
+mod headless;
+mod input;
 mod render;
+mod sim;
+mod units;
 
+use std::env;
+use std::process;
 use std::time::{Duration, Instant};
 use std::thread;
 
 // TODO: move window construction to render module?
 use glium::DisplayBuild;
-use glium::glutin::{Event, ElementState, VirtualKeyCode as VKC, WindowBuilder};
+use glium::glutin::{Event, WindowBuilder};
 
-use input::Input;
-use render::{RenderGroup, RenderJob};
-use units::linear::V2;
+use camera::DEFAULT_VIEWPORT;
+use clock::{FixedStep, MAX_FRAME_MS, SIM_RATE_HZ};
+use console::Console;
+use entities::map::STAR_SEED;
+use input::{Bindings, Input, InputEvent, InputRecorder, InputScript, Key};
+use render::{Align, RenderGroup, RenderJob, Text};
+use sim::Simulation;
 
 static TARGET_FPS_MS: u64 = 1000 / 120;
+static PAUSED_TEXT_SIZE: f32 = 32.0;
+static BINDINGS_PATH: &'static str = "assets/config/bindings.cfg";
+
+static USAGE: &'static str = "usage: megumin [--script FILE] [--record FILE] [--headless [--ticks N]]";
+
+/// Options parsed from the command line.
+struct Options {
+    headless: bool,
+    ticks:    Option<u64>,
+    script:   Option<String>,
+    record:   Option<String>,
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut opts = Options { headless: false, ticks: None, script: None, record: None };
+    let mut args = env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match &arg[..] {
+            "--headless" => opts.headless = true,
+
+            "--ticks" => opts.ticks = match args.next().and_then(|n| n.parse().ok()) {
+                Some(ticks) => Some(ticks),
+                None => return Err("--ticks expects a number".to_string()),
+            },
+
+            "--record" => opts.record = match args.next() {
+                Some(path) => Some(path),
+                None => return Err("--record expects a path".to_string()),
+            },
+
+            "--script" => opts.script = match args.next() {
+                Some(path) => Some(path),
+                None => return Err("--script expects a path".to_string()),
+            },
+
+            _ => return Err(format!("unknown argument: {}", arg)),
+        }
+    }
+
+    Ok(opts)
+}
 
 fn main() {
+    let opts = match parse_args() {
+        Ok(opts) => opts,
+        Err(msg) => { println!("{}\n{}", msg, USAGE); process::exit(1) },
+    };
+
+    let script = opts.script.map(|path| {
+        InputScript::load(&path).unwrap_or_else(|err| {
+            println!("could not load script: {}", err); process::exit(1)
+        })
+    });
+
+    let bindings = Bindings::load_or_default(BINDINGS_PATH);
+
+    if opts.headless {
+        headless::run(opts.ticks, script.unwrap_or_else(InputScript::new), bindings);
+    } else {
+        run_windowed(script, opts.record, bindings);
+    }
+}
+
+/// Runs the game in a window. If a `replay` script is given it drives
+/// the simulation instead of the keyboard, if `record_path` is given
+/// all input is recorded there so the session can be replayed later.
+fn run_windowed(mut replay: Option<InputScript>, record_path: Option<String>, bindings: Bindings) {
     // setup hardware
     println!("initializing display ...");
     let display = WindowBuilder::new()
-                                .with_dimensions(1280, 720)
+                                .with_dimensions(DEFAULT_VIEWPORT.0, DEFAULT_VIEWPORT.1)
                                 .with_depth_buffer(24)
                                 .with_title("megumin")
                                 .build_glium()
@@ -43,20 +122,37 @@ fn main() {
     };
 
 
+    // the window actually on screen, which a replay's simulation may not match
+    let mut window_size = display.get_framebuffer_dimensions();
+
+    // replays must be simulated w/ the same settings they were recorded with
+    let seed = replay.as_ref().and_then(|script| script.seed()).unwrap_or(STAR_SEED);
+    let step = replay.as_ref().and_then(|script| script.timestep())
+                     .unwrap_or_else(|| clock::step_for_hz(SIM_RATE_HZ));
+
     // TODO: engine state block
     let mut controller  =  Input::new();
-    let mut renderer    = RenderGroup::new(&display, &draw_params);
+    let mut console     =  Console::new();
+    controller.set_bindings(bindings);
+    controller.apply(InputEvent::Resized(window_size.0, window_size.1));
+    if let Some(ref script) = replay { script.configure(&mut controller); }
+
+    let mut recorder = record_path.map(|path| {
+        InputRecorder::create(&path, step, seed, &controller).expect("could not create input recording")
+    });
+    let mut renderer    = RenderGroup::new(&display, &draw_params).unwrap_or_else(|err| {
+        println!("could not initialize renderer: {}", err); process::exit(1)
+    });
     let mut render_jobs = vec![];
-   
-    // TODO: some sort of entity buffer
-    let mut world  = entities::World::new(&mut renderer, V2::at(0.5, 0.5));
-    let mut player = entities::Sprite::new(&mut renderer);
+    let mut sim         = Simulation::new(&mut renderer, seed);
+    let mut gamepads    = input::gamepad::connect();
+    let mut live_input  = vec![];
 
     // the runloop is a fairly straightforward game loop, it spends time performing
     // three major functions:
     //
-    // - buffering input
-    // - integrating entities over time (elapsed since last frame)
+    // - buffering input (from the window & any gamepads)
+    // - integrating entities over time (in fixed steps)
     // - rendering active entities
     //
     // at the top of each frame we compute the time elapsed since the last
@@ -65,15 +161,28 @@ fn main() {
     //
     // input is buffered into a series of tables. these tables are optionally
     // used by entities to determine their behavior for the next simulation step.
+    // the pressed/released tables are only reset once a simulation step has
+    // consumed them, so a keypress is seen exactly once even if this frame
+    // runs zero (or several) steps.
     //
-    // this delta is used to drive the simulation step at a constant rate in
-    // terms of milliseconds elapsed. this value will be higher if the runloop
-    // is running behind. physics should therefore be in a coherent state wrt
-    // the computer's real-time clock.
+    // every event is tagged w/ the step which will first see it, so a
+    // recording of these events reproduces the session step-for-step.
+    //
+    // if the window loses focus every held key is released, and the game
+    // pauses by withholding time from the simulation until focus returns.
+    //
+    // this delta is fed into an accumulator which drives the simulation at
+    // a constant rate of `SIM_RATE_HZ` steps per second. if the runloop is
+    // running behind several steps are run back to back to catch up, though
+    // frames longer than `MAX_FRAME_MS` are clamped so we never fall into a
+    // spiral of death. physics is therefore independent of the frame rate,
+    // while remaining coherent wrt the computer's real-time clock.
     //
     // after each active entitiy has been simulated we begin rendering the
     // world. this is done by grabbing the backbuffer, clearing it, and
-    // allowing each entity to mutate the render queue serially.
+    // allowing each entity to mutate the render queue serially. entities
+    // are drawn blended between their previous & current step, using
+    // the fraction of a step left over in the accumulator.
     //
     // there is probably potential for threading & perf wins here, not sure.
     // (possibly need to designate "layers" (fg/mg/bg) as sync points
@@ -82,14 +191,12 @@ fn main() {
     //  the renderer is then instructed to commit the render queue to the
     //  backbuffer. the details of this are a mystery.
     //
-    // TODO: cap the `frame_dt` to a fixed timestep to allow for easier
-    // debugging, etc.
-    //
     // TODO: display building config. (vsync, resolution?, windowed?, etc.)
     //
 
     // game clock
     let target_fps      = Duration::from_millis(TARGET_FPS_MS);
+    let mut sim_clock   = FixedStep::new(step, Duration::from_millis(MAX_FRAME_MS));
     let mut frame_start = Instant::now();
 
     println!("starting game loop ...");
@@ -100,41 +207,77 @@ fn main() {
         //
         let frame_dt = Instant::now() - frame_start; // rtc since last frame
         frame_start = Instant::now();                // reset game clock
-        controller.begin_new_frame();                // reset input buffer
         render_jobs.clear();                         // clear render queue
 
 
-        // store frame inputs in buffer
+        // gather frame inputs from the window & any gamepads
+        live_input.clear();
         for ev in display.poll_events() {
             match ev {
                 Event::Closed => break 'runloop,
-
-                // keyboard
-                Event::KeyboardInput(ElementState::Pressed,  _code, Some(cap)) => controller.key_down_event(cap),
-                Event::KeyboardInput(ElementState::Released, _code, Some(cap)) => controller.key_up_event(cap),
                 Event::KeyboardInput(_, code, None) => println!("uknown key code: {}", code),
 
-                // mouse (x grows right, y grows downward)
-                Event::MouseMoved(mx, my) => controller.move_cursor(mx, my),
-                _ => (),
+                ref ev => if let Some(input_ev) = input::glutin::translate_event(ev) {
+                    live_input.push(input_ev);
+                },
+            }
+        }
+
+        for pad in &mut gamepads { pad.poll(&mut live_input); }
+
+        // store frame inputs in buffer
+        for &input_ev in &live_input {
+            if let InputEvent::Resized(w, h) = input_ev { window_size = (w, h); }
+
+            // live input is ignored during a replay, except to bail out
+            if replay.is_some() {
+                if let InputEvent::KeyDown(Key::Escape) = input_ev { break 'runloop }
+                continue
             }
+
+            if let Some(ref mut recorder) = recorder {
+                recorder.record(sim.tick(), input_ev).expect("could not write input recording");
+            }
+
+            controller.apply(input_ev);
+        }
+
+        // step simulation, unless the game was paused by losing focus.
+        // (a replay carries on regardless: its own focus events only arrive
+        // as steps are run, so pausing on them would stall it for good.)
+        let paused   = replay.is_none() && !controller.is_focused();
+        let frame_dt = if paused { Duration::new(0, 0) } else { frame_dt };
+        if sim_clock.accumulate(frame_dt) { println!("clamped frame {:?}", frame_dt); }
+        while sim_clock.tick() {
+            if let Some(ref mut script) = replay { script.apply(sim.tick(), &mut controller); }
+
+            // process input buffer (escape is just a key while typing)
+            if controller.was_key_pressed(Key::Escape) && !controller.is_text_input() { break 'runloop }
+            console.update(&mut controller);
+            sim.step(&controller, sim_clock.step());
+            controller.begin_new_frame(); // reset input buffer
         }
 
-        // process input buffer
-        if controller.was_key_pressed(VKC::Escape) { break 'runloop }
-        player.update(&controller, frame_dt);
-        world.update(player.position());
+        let alpha = sim_clock.alpha();
 
         // TODO: use depth buffer instead of relying on draw order
         // prepare render queue
         render_jobs.push(RenderJob::ClearScreen(0.0, 0.0, 0.0, 1.0));
         render_jobs.push(RenderJob::ClearDepth(1.0));
-        player.draw(&mut render_jobs);
-        world.draw(&mut render_jobs);
+        sim.draw(&mut render_jobs, alpha);
+        console.draw(&controller, window_size, &mut render_jobs);
+
+        if paused {
+            let (w, h) = window_size;
+            let label  = Text::from("paused", w as f32 / 2.0, h as f32 / 2.0).size(PAUSED_TEXT_SIZE).align(Align::Center);
+            render_jobs.push(RenderJob::DrawText(label));
+        }
 
         // draw queue to back buffer
         let mut frame = display.draw();
-        renderer.draw(&render_jobs[..], &mut frame);
+        if let Err(err) = renderer.draw(&render_jobs[..], &sim.view(alpha, window_size), &mut frame) {
+            println!("could not draw frame: {}", err);
+        }
         frame.finish().unwrap();
 
         // handle frame timing
@@ -144,5 +287,9 @@ fn main() {
         thread::sleep(draw_time);
     }
 
+    if let Some(recorder) = recorder {
+        recorder.finish(sim.tick()).expect("could not finish input recording");
+    }
+
     println!("goodbye ...");
 }
@@ -0,0 +1,187 @@
+use std::rc::Rc;
+use std::time::Duration;
+
+use render::{SpriteSheet, TextureHandle};
+use units::dt2ms_f32;
+
+/// What an animation does once it reaches its last frame.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Playback {
+    /// Stops on the last frame.
+    #[allow(dead_code)]
+    Once,
+    /// Starts over from the first frame.
+    #[allow(dead_code)]
+    Loop,
+    /// Plays backward to the first frame, then forward again, and so on.
+    PingPong,
+}
+
+/// A sequence of frames from a `SpriteSheet`, each shown for its own
+/// duration (in ms.) An animation always has at least one frame, so it
+/// is built from its first frame & extended w/ `frame()`.
+pub struct Animation {
+    frames:   Vec<(usize, f32)>,
+    playback: Playback,
+}
+
+impl Animation {
+    /// An animation which starts on frame `idx` of the sheet, shown for `duration_ms`.
+    pub fn new(playback: Playback, idx: usize, duration_ms: f32) -> Self {
+        Animation { frames: vec![], playback: playback }.frame(idx, duration_ms)
+    }
+
+    /// An animation which shows each of `frames` for `duration_ms`.
+    /// Responds `None` if `frames` is empty.
+    #[allow(dead_code)]
+    pub fn uniform(playback: Playback, frames: &[usize], duration_ms: f32) -> Option<Self> {
+        let (&first, rest) = frames.split_first()?;
+        Some(rest.iter().fold(Animation::new(playback, first, duration_ms), |anim, &idx| anim.frame(idx, duration_ms)))
+    }
+
+    /// Appends frame `idx` of the sheet, shown for `duration_ms`.
+    /// (Durations are at least 1ms, so an update always ends.)
+    pub fn frame(mut self, idx: usize, duration_ms: f32) -> Self {
+        self.frames.push((idx, duration_ms.max(1.0)));
+        self
+    }
+}
+
+/// Steps through an `Animation`, keeping track of which frame of its
+/// sheet should currently be drawn.
+pub struct AnimationPlayer {
+    sheet:     Rc<SpriteSheet>,
+    animation: Rc<Animation>,
+
+    step:     usize,
+    elapsed:  f32,
+    reversed: bool,
+    finished: bool,
+}
+
+impl AnimationPlayer {
+    pub fn new(sheet: Rc<SpriteSheet>, animation: Rc<Animation>) -> Self {
+        AnimationPlayer {
+            sheet:     sheet,
+            animation: animation,
+
+            step:     0,
+            elapsed:  0.0,
+            reversed: false,
+            finished: false,
+        }
+    }
+
+    /// Switches to `animation` & starts it from the beginning, unless it is
+    /// already the one being played.
+    pub fn play(&mut self, animation: &Rc<Animation>) {
+        if Rc::ptr_eq(&self.animation, animation) { return }
+
+        self.animation = animation.clone();
+        self.restart();
+    }
+
+    /// Starts the current animation over from its first frame.
+    pub fn restart(&mut self) {
+        self.step     = 0;
+        self.elapsed  = 0.0;
+        self.reversed = false;
+        self.finished = false;
+    }
+
+    /// Responds true once an animation which plays `Once` reaches its end.
+    #[allow(dead_code)]
+    pub fn is_finished(&self) -> bool { self.finished }
+
+    /// Advances the animation by `dt`, possibly skipping several frames.
+    pub fn update(&mut self, dt: Duration) {
+        self.elapsed += dt2ms_f32(dt);
+
+        while !self.finished {
+            let duration = self.animation.frames[self.step].1;
+            if self.elapsed < duration { break }
+
+            self.elapsed -= duration;
+            self.advance();
+        }
+    }
+
+    /// A handle to draw the frame which is currently showing.
+    pub fn texture(&self) -> TextureHandle {
+        self.sheet.frame(self.animation.frames[self.step].0)
+    }
+
+    fn advance(&mut self) {
+        let last = self.animation.frames.len() - 1;
+
+        match self.animation.playback {
+            Playback::Once if self.step == last => { self.finished = true; self.elapsed = 0.0 },
+            Playback::Once => self.step += 1,
+            Playback::Loop => self.step = if self.step == last { 0 } else { self.step + 1 },
+
+            Playback::PingPong if last == 0 => (),
+            Playback::PingPong => {
+                if self.step == last { self.reversed = true }
+                if self.step == 0    { self.reversed = false }
+                if self.reversed { self.step -= 1 } else { self.step += 1 }
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+    use std::time::Duration;
+
+    use render::{NullTextures, SpriteSheet, TextureStore};
+    use super::{Animation, AnimationPlayer, Playback};
+
+    // plays `anim` in 10ms steps, noting the frame shown after each
+    fn steps(anim: Animation, count: usize) -> (Vec<usize>, AnimationPlayer) {
+        let sheet  = Rc::new(SpriteSheet::grid(NullTextures::new().fallback(), 4, 1));
+        let mut player = AnimationPlayer::new(sheet, Rc::new(anim));
+
+        let shown = (0..count).map(|_| {
+            player.update(Duration::from_millis(10));
+            player.step
+        }).collect();
+
+        (shown, player)
+    }
+
+    #[test]
+    fn once_stops_on_the_last_frame() {
+        let (shown, player) = steps(Animation::uniform(Playback::Once, &[0, 1, 2], 10.0).unwrap(), 4);
+        assert_eq!(shown, vec![1, 2, 2, 2]);
+        assert!(player.is_finished());
+    }
+
+    #[test]
+    fn loop_starts_over() {
+        let (shown, player) = steps(Animation::uniform(Playback::Loop, &[0, 1, 2], 10.0).unwrap(), 5);
+        assert_eq!(shown, vec![1, 2, 0, 1, 2]);
+        assert!(!player.is_finished());
+    }
+
+    #[test]
+    fn ping_pong_bounces_between_the_ends() {
+        let (shown, _) = steps(Animation::uniform(Playback::PingPong, &[0, 1, 2], 10.0).unwrap(), 6);
+        assert_eq!(shown, vec![1, 2, 1, 0, 1, 2]);
+
+        let (shown, _) = steps(Animation::new(Playback::PingPong, 3, 10.0), 3);
+        assert_eq!(shown, vec![0, 0, 0]);
+    }
+
+    #[test]
+    fn long_updates_skip_frames() {
+        let anim = Animation::new(Playback::Loop, 0, 10.0).frame(1, 30.0).frame(2, 10.0);
+        let (shown, _) = steps(anim, 6);
+        assert_eq!(shown, vec![1, 1, 1, 2, 0, 1]);
+    }
+
+    #[test]
+    fn rejects_empty_animations() {
+        assert!(Animation::uniform(Playback::Loop, &[], 10.0).is_none());
+    }
+}
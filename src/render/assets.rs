@@ -0,0 +1,165 @@
+use std::collections::HashMap;
+
+use render::UvRect;
+
+/// Identifies a texture held by a `TextureStore`.
+///
+/// Ids are never reused, so a handle to a texture which has since been
+/// unloaded can't accidentally draw some other texture; the renderer draws
+/// the fallback (checkerboard) texture in its place.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TextureId(usize);
+
+/// Refers to part of a texture held by a `TextureStore`.
+///
+/// The UVs are relative to the texture itself, the renderer works out
+/// where the texture actually lives (e.g: which atlas page) when drawing.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TextureHandle {
+    pub id: TextureId,
+    pub uv: UvRect,
+}
+
+impl TextureHandle {
+    /// A handle covering all of texture `id`.
+    pub fn whole(id: TextureId) -> Self { TextureHandle { id: id, uv: UvRect::whole() } }
+}
+
+struct Entry<T> {
+    path:  Option<String>,
+    refs:  usize,
+    value: T,
+}
+
+/// Keeps track of loaded assets & how many times each has been loaded.
+///
+/// Assets loaded from a path are shared: loading the same path again hands
+/// back the same id (and adds a reference), rather than loading it twice.
+/// An asset is unloaded once every reference to it has been released.
+pub struct Registry<T> {
+    entries: Vec<Option<Entry<T>>>,
+    paths:   HashMap<String, TextureId>,
+}
+
+impl<T> Registry<T> {
+    pub fn new() -> Self {
+        Registry { entries: vec![], paths: HashMap::new() }
+    }
+
+    /// Adds a reference to the asset loaded from `path`, if there is one.
+    pub fn acquire(&mut self, path: &str) -> Option<TextureId> {
+        let id = self.find(path)?;
+        self.retain(id);
+        Some(id)
+    }
+
+    /// The asset loaded from `path`, if there is one, w/o adding a reference.
+    pub fn find(&self, path: &str) -> Option<TextureId> {
+        self.paths.get(path).cloned()
+    }
+
+    /// Registers a newly loaded asset, w/ a single reference to it.
+    /// Assets w/o a path are never shared.
+    pub fn insert(&mut self, path: Option<&str>, value: T) -> TextureId {
+        let id = TextureId(self.entries.len());
+        if let Some(path) = path { self.paths.insert(path.to_string(), id); }
+
+        self.entries.push(Some(Entry { path: path.map(str::to_string), refs: 1, value: value }));
+        id
+    }
+
+    /// Adds a reference to asset `id`, if it is still loaded.
+    pub fn retain(&mut self, id: TextureId) {
+        if let Some(entry) = self.entry_mut(id) { entry.refs += 1; }
+    }
+
+    /// Drops a reference to asset `id`, unloading it (and handing it back)
+    /// once there are none left.
+    pub fn release(&mut self, id: TextureId) -> Option<T> {
+        {
+            let entry = self.entry_mut(id)?;
+            entry.refs -= 1;
+            if entry.refs > 0 { return None }
+        }
+
+        let entry = self.entries[id.0].take()?;
+        if let Some(ref path) = entry.path { self.paths.remove(path); }
+        Some(entry.value)
+    }
+
+    pub fn get(&self, id: TextureId) -> Option<&T> {
+        self.entries.get(id.0).and_then(|entry| entry.as_ref()).map(|entry| &entry.value)
+    }
+
+    pub fn get_mut(&mut self, id: TextureId) -> Option<&mut T> {
+        self.entry_mut(id).map(|entry| &mut entry.value)
+    }
+
+    /// The path asset `id` was loaded from, if it was loaded from disk.
+    #[allow(dead_code)]
+    pub fn path(&self, id: TextureId) -> Option<&str> {
+        self.entries.get(id.0).and_then(|entry| entry.as_ref()).and_then(|entry| entry.path.as_ref()).map(|path| &path[..])
+    }
+
+    /// Number of references held to asset `id`, zero once it is unloaded.
+    pub fn refs(&self, id: TextureId) -> usize {
+        self.entries.get(id.0).and_then(|entry| entry.as_ref()).map_or(0, |entry| entry.refs)
+    }
+
+    fn entry_mut(&mut self, id: TextureId) -> Option<&mut Entry<T>> {
+        self.entries.get_mut(id.0).and_then(|entry| entry.as_mut())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Registry;
+
+    #[test]
+    fn shares_assets_by_path() {
+        let mut registry = Registry::new();
+        let id = registry.insert(Some("ship.tga"), 'a');
+
+        assert_eq!(registry.acquire("ship.tga"), Some(id));
+        assert_eq!(registry.find("ship.tga"), Some(id));
+        assert_eq!(registry.refs(id), 2);
+        assert_eq!(registry.path(id), Some("ship.tga"));
+        assert_eq!(registry.acquire("other.tga"), None);
+    }
+
+    #[test]
+    fn unloads_after_the_last_release() {
+        let mut registry = Registry::new();
+        let id = registry.insert(Some("ship.tga"), 'a');
+        registry.retain(id);
+
+        assert_eq!(registry.release(id), None);
+        assert_eq!(registry.get(id), Some(&'a'));
+
+        assert_eq!(registry.release(id), Some('a'));
+        assert_eq!(registry.get(id), None);
+        assert_eq!(registry.refs(id), 0);
+        assert_eq!(registry.find("ship.tga"), None);
+
+        // releasing (or retaining) an unloaded asset does nothing
+        registry.retain(id);
+        assert_eq!(registry.release(id), None);
+    }
+
+    #[test]
+    fn never_reuses_ids() {
+        let mut registry = Registry::new();
+        let first = registry.insert(Some("ship.tga"), 'a');
+        registry.release(first);
+
+        let second = registry.insert(Some("ship.tga"), 'b');
+        assert!(first != second);
+        assert_eq!(registry.get(first), None);
+        assert_eq!(registry.get(second), Some(&'b'));
+
+        // assets w/o a path are never shared
+        let unnamed = registry.insert(None, 'c');
+        assert_eq!(registry.path(unnamed), None);
+        assert_eq!(registry.refs(unnamed), 1);
+    }
+}
@@ -0,0 +1,252 @@
+// width & height of a shared atlas page, in pixels
+pub static PAGE_SIZE: u32 = 1024;
+
+// empty pixels left around each image, so neighbours don't bleed together
+pub static PADDING: u32 = 1;
+
+/// A sub-rectangle of a texture in UV space, (0,0) is the first pixel
+/// row & column of the image the texture was built from.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct UvRect {
+    pub u1: f32, pub v1: f32,
+    pub u2: f32, pub v2: f32,
+}
+
+impl UvRect {
+    /// The entirety of a texture.
+    pub fn whole() -> Self { UvRect { u1: 0.0, v1: 0.0, u2: 1.0, v2: 1.0 } }
+
+    /// Covers exactly the `w` x `h` texels starting at `(x, y)` on a page.
+    pub fn texels(x: u32, y: u32, w: u32, h: u32, page_w: u32, page_h: u32) -> UvRect {
+        let (pw, ph) = (page_w as f32, page_h as f32);
+
+        UvRect {
+            u1: x as f32 / pw,          v1: y as f32 / ph,
+            u2: (x + w) as f32 / pw,    v2: (y + h) as f32 / ph,
+        }
+    }
+
+    /// Moves each edge `du` (or `dv`) toward the middle of the rect, even
+    /// if the rect is flipped.
+    pub fn inset(&self, du: f32, dv: f32) -> UvRect {
+        let du = if self.u1 <= self.u2 { du } else { -du };
+        let dv = if self.v1 <= self.v2 { dv } else { -dv };
+
+        UvRect {
+            u1: self.u1 + du, v1: self.v1 + dv,
+            u2: self.u2 - du, v2: self.v2 - dv,
+        }
+    }
+
+    /// The part of this rect covering `(x, y) .. (x + w, y + h)`, where each
+    /// of those are a fraction (0.0 .. 1.0) of this rect's width & height.
+    pub fn sub(&self, x: f32, y: f32, w: f32, h: f32) -> UvRect {
+        let (du, dv) = (self.u2 - self.u1, self.v2 - self.v1);
+
+        UvRect {
+            u1: self.u1 + (du * x),       v1: self.v1 + (dv * y),
+            u2: self.u1 + (du * (x + w)), v2: self.v1 + (dv * (y + h)),
+        }
+    }
+
+    /// The part of this rect covered by `inner`, which is measured in
+    /// fractions of this rect (like the arguments of `sub()`.)
+    pub fn map(&self, inner: UvRect) -> UvRect {
+        self.sub(inner.u1, inner.v1, inner.u2 - inner.u1, inner.v2 - inner.v1)
+    }
+}
+
+/// Where an image was placed by `AtlasPacker::pack()`.
+pub struct Placement {
+    pub page:     usize,
+    pub x:        u32,
+    pub y:        u32,
+    pub new_page: Option<(u32, u32)>,
+}
+
+struct Shelf { y: u32, height: u32, used: u32 }
+
+struct Page { width: u32, height: u32, shelves: Vec<Shelf>, used: u32, free: Vec<Region> }
+
+// a released (padded) area of a page: x, y, width, height
+type Region = (u32, u32, u32, u32);
+
+/// Packs images into shared pages using rows of images ("shelves".)
+///
+/// Each image is placed on the first shelf which is tall enough & has room
+/// left, otherwise a new shelf is opened at the top of the page. Images too
+/// large to ever share a page are given a page of their own.
+///
+/// Released images leave a hole behind, which is handed to the next image
+/// that fits in it. A smaller image takes the hole's bottom-left corner, the
+/// space left over to its right & above it become holes of their own.
+/// (Holes are never merged back together though.)
+pub struct AtlasPacker {
+    pages: Vec<Page>,
+}
+
+impl AtlasPacker {
+    pub fn new() -> Self { AtlasPacker { pages: vec![] } }
+
+    /// The size of `page` in pixels.
+    pub fn page_size(&self, page: usize) -> (u32, u32) {
+        (self.pages[page].width, self.pages[page].height)
+    }
+
+    /// Finds room for a `w` x `h` image (plus padding), opening a new page
+    /// if none of the existing ones can fit it. Responds `None` if there is
+    /// no way to place the image at all.
+    pub fn pack(&mut self, w: u32, h: u32) -> Option<Placement> {
+        let (w, h) = (w.checked_add(PADDING * 2)?, h.checked_add(PADDING * 2)?);
+
+        if w > PAGE_SIZE || h > PAGE_SIZE {
+            return Some(self.open_page(w, h));
+        }
+
+        for (idx, page) in self.pages.iter_mut().enumerate() {
+            if let Some((x, y)) = page.fit(w, h) {
+                return Some(Placement { page: idx, x: x, y: y, new_page: None });
+            }
+        }
+
+        let mut placement = self.open_page(PAGE_SIZE, PAGE_SIZE);
+        match self.pages[placement.page].fit(w, h) {
+            Some((x, y)) => { placement.x = x; placement.y = y; Some(placement) },
+            None => { self.discard_page(placement.page); None },
+        }
+    }
+
+    /// Gives back the room used by a `w` x `h` image, which was placed at
+    /// `(x, y)` on `page`. (Measured inside its padding, like `pack()`.)
+    pub fn release(&mut self, page: usize, x: u32, y: u32, w: u32, h: u32) {
+        let region = (x - PADDING, y - PADDING, w + (PADDING * 2), h + (PADDING * 2));
+        self.pages[page].free.push(region);
+    }
+
+    /// Forgets the most recently opened `page`, e.g: when the GPU could
+    /// not allocate it after all.
+    pub fn discard_page(&mut self, page: usize) {
+        if page + 1 == self.pages.len() { self.pages.pop(); }
+    }
+
+    fn open_page(&mut self, width: u32, height: u32) -> Placement {
+        // a dedicated page is fully used by its only image
+        let used = if width == PAGE_SIZE && height == PAGE_SIZE { 0 } else { height };
+        self.pages.push(Page { width: width, height: height, shelves: vec![], used: used, free: vec![] });
+
+        Placement { page: self.pages.len() - 1, x: 0, y: 0, new_page: Some((width, height)) }
+    }
+}
+
+impl Page {
+    fn fit(&mut self, w: u32, h: u32) -> Option<(u32, u32)> {
+        // prefer the smallest hole which fits, to waste as little as possible
+        let hole = self.free.iter().enumerate()
+                       .filter(|&(_, &(_, _, fw, fh))| fw >= w && fh >= h)
+                       .min_by_key(|&(_, &(_, _, fw, fh))| fw * fh)
+                       .map(|(idx, _)| idx);
+
+        if let Some(idx) = hole {
+            let (x, y, fw, fh) = self.free.swap_remove(idx);
+            if fw > w { self.free.push((x + w, y, fw - w, h)); }
+            if fh > h { self.free.push((x, y + h, fw, fh - h)); }
+            return Some((x, y));
+        }
+
+        let width = self.width;
+        if let Some(shelf) = self.shelves.iter_mut().find(|s| s.height >= h && width - s.used >= w) {
+            shelf.used += w;
+            return Some((shelf.used - w, shelf.y));
+        }
+
+        if self.height - self.used < h { return None }
+        self.shelves.push(Shelf { y: self.used, height: h, used: w });
+        self.used += h;
+        Some((0, self.used - h))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AtlasPacker, UvRect, PADDING, PAGE_SIZE};
+
+    // where the packer put an image, measured inside its padding
+    fn pack(packer: &mut AtlasPacker, w: u32, h: u32) -> (usize, u32, u32) {
+        let placement = packer.pack(w, h).unwrap();
+        (placement.page, placement.x + PADDING, placement.y + PADDING)
+    }
+
+    #[test]
+    fn fills_shelves_left_to_right() {
+        let mut packer = AtlasPacker::new();
+        let p = PADDING * 2;
+
+        assert_eq!(pack(&mut packer, 30, 30), (0, 1, 1));
+        assert_eq!(pack(&mut packer, 30, 20), (0, 30 + p + 1, 1));
+
+        // too tall for the first shelf, so a new one opens above it
+        assert_eq!(pack(&mut packer, 10, 40), (0, 1, 30 + p + 1));
+        assert_eq!(packer.page_size(0), (PAGE_SIZE, PAGE_SIZE));
+    }
+
+    #[test]
+    fn opens_a_page_when_full() {
+        let mut packer = AtlasPacker::new();
+        let half = (PAGE_SIZE / 2) - (PADDING * 2);
+
+        for _ in 0..4 { assert_eq!(pack(&mut packer, half, half).0, 0); }
+
+        let placement = packer.pack(half, half).unwrap();
+        assert_eq!((placement.page, placement.x, placement.y), (1, 0, 0));
+        assert_eq!(placement.new_page, Some((PAGE_SIZE, PAGE_SIZE)));
+    }
+
+    #[test]
+    fn gives_large_images_their_own_page() {
+        let mut packer = AtlasPacker::new();
+        pack(&mut packer, 8, 8);
+
+        let placement = packer.pack(PAGE_SIZE, 16).unwrap();
+        assert_eq!(placement.page, 1);
+        assert_eq!(placement.new_page, Some((PAGE_SIZE + PADDING * 2, 16 + PADDING * 2)));
+
+        // which nothing else is placed on
+        assert_eq!(pack(&mut packer, 8, 8).0, 0);
+    }
+
+    #[test]
+    fn reuses_released_room() {
+        let mut packer = AtlasPacker::new();
+        let (_, x, y) = pack(&mut packer, 30, 30);
+        pack(&mut packer, 30, 30);
+        packer.release(0, x, y, 30, 30);
+
+        // a smaller image takes the hole's corner ...
+        assert_eq!(pack(&mut packer, 10, 10), (0, x, y));
+
+        // ... and what's left over is handed out after it
+        let p = PADDING * 2;
+        assert_eq!(pack(&mut packer, 18, 10), (0, x + 10 + p, y));
+        assert_eq!(pack(&mut packer, 30, 18), (0, x, y + 10 + p));
+
+        // until the hole is used up
+        assert!(pack(&mut packer, 5, 5).1 > 30 * 2);
+    }
+
+    #[test]
+    fn rejects_impossible_images() {
+        let mut packer = AtlasPacker::new();
+        assert!(packer.pack(u32::max_value(), 8).is_none());
+    }
+
+    #[test]
+    fn frames_land_on_texel_edges() {
+        // the right half of a 4x8 image, inset by half a texel
+        let image = UvRect::texels(2, 4, 4, 8, 16, 16);
+        let frame = image.sub(0.5, 0.0, 0.5, 1.0).inset(0.5 / 16.0, 0.5 / 16.0);
+        assert_eq!(frame, UvRect { u1: 4.5 / 16.0, v1: 4.5 / 16.0, u2: 5.5 / 16.0, v2: 11.5 / 16.0 });
+
+        let flipped = UvRect { u1: 1.0, v1: 0.0, u2: 0.0, v2: 1.0 }.inset(0.25, 0.25);
+        assert_eq!(flipped, UvRect { u1: 0.75, v1: 0.25, u2: 0.25, v2: 0.75 });
+    }
+}
@@ -0,0 +1,62 @@
+use std::fs::File;
+use std::io::Read;
+
+use image::{self, ImageFormat};
+
+use error::Result;
+use units::drawing::RGBA;
+
+/// How an image's color channels relate to its alpha channel.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Alpha {
+    /// Colors are stored as-is, this is what the renderer's blending expects.
+    Straight,
+
+    /// Colors are multiplied by alpha, for use w/ a blend function which
+    /// expects it: `source: One, destination: OneMinusSourceAlpha`.
+    #[allow(dead_code)]
+    Premultiplied,
+}
+
+/// Reads the image at `path` into rows of pixels, bottom row first.
+///
+/// The format is worked out from the first few bytes of the file rather
+/// than its extension; TGAs have no such signature, so anything which isn't
+/// recognized is read as a TGA. Grayscale, RGB & paletted images are all
+/// expanded to RGBA.
+pub fn read_image(path: &str, alpha: Alpha) -> Result<Vec<Vec<RGBA>>> {
+    let mut bytes = vec![];
+    File::open(path)?.read_to_end(&mut bytes)?;
+    decode_image(&bytes, alpha)
+}
+
+/// Decodes an image file which has already been read into memory, see
+/// `read_image()`.
+pub fn decode_image(bytes: &[u8], alpha: Alpha) -> Result<Vec<Vec<RGBA>>> {
+    let format = sniff_format(bytes);
+    let rgba   = image::load_from_memory_with_format(bytes, format)?.to_rgba();
+
+    let width = rgba.width() as usize;
+    let mut buf: Vec<Vec<RGBA>> = rgba.into_raw().chunks(width.max(1) * 4).map(|row| {
+        row.chunks(4).map(|px| match alpha {
+            Alpha::Straight      => (px[0], px[1], px[2], px[3]),
+            Alpha::Premultiplied => (premultiply(px[0], px[3]), premultiply(px[1], px[3]), premultiply(px[2], px[3]), px[3]),
+        }).collect()
+    }).collect();
+
+    // every other format is decoded top row first, while our TGAs are
+    // stored (and decoded) bottom row first, which is what GL expects.
+    if format != ImageFormat::TGA { buf.reverse(); }
+
+    Ok(buf)
+}
+
+/// Guesses the format of an image file from its signature, falling back
+/// to TGA (which doesn't have one.)
+pub fn sniff_format(bytes: &[u8]) -> ImageFormat {
+    image::guess_format(bytes).unwrap_or(ImageFormat::TGA)
+}
+
+fn premultiply(channel: u8, alpha: u8) -> u8 {
+    ((channel as u32 * alpha as u32 + 127) / 255) as u8
+}
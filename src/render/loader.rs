@@ -0,0 +1,77 @@
+use std::sync::{Arc, Mutex};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+use error::Result;
+use render::TextureId;
+use render::decode::{read_image, Alpha};
+use units::drawing::RGBA;
+
+// number of threads decoding images in the background
+static LOADER_THREADS: usize = 2;
+
+/// An image which has finished loading, or failed to.
+pub struct Loaded {
+    pub id:     TextureId,
+    pub path:   String,
+    pub pixels: Result<Vec<Vec<RGBA>>>,
+}
+
+/// Reads & decodes images on a small pool of worker threads.
+///
+/// Only the decoding happens in the background: the GL context belongs to
+/// the render thread, so the renderer picks up finished images each frame
+/// (see `Loader::finished()`) and uploads them itself.
+pub struct Loader {
+    requests: Sender<(TextureId, String)>,
+    finished: Receiver<Loaded>,
+    pending:  usize,
+}
+
+impl Loader {
+    pub fn new() -> Result<Self> {
+        let (requests, queue) = mpsc::channel();
+        let (done, finished)  = mpsc::channel();
+        let queue = Arc::new(Mutex::new(queue));
+
+        for worker_no in 0..LOADER_THREADS {
+            let (queue, done) = (queue.clone(), done.clone());
+            thread::Builder::new()
+                .name(format!("loader-{}", worker_no))
+                .spawn(move || worker(queue, done))?;
+        }
+
+        Ok(Loader { requests: requests, finished: finished, pending: 0 })
+    }
+
+    /// Queues the image at `path` to be loaded for texture `id`.
+    pub fn request(&mut self, id: TextureId, path: &str) {
+        // the workers only stop once this end of the queue is dropped
+        if self.requests.send((id, path.to_string())).is_ok() { self.pending += 1; }
+    }
+
+    /// Every image which has finished loading since the last call.
+    pub fn finished(&mut self) -> Vec<Loaded> {
+        let loaded: Vec<Loaded> = self.finished.try_iter().collect();
+        self.pending -= loaded.len();
+        loaded
+    }
+
+    /// Number of images which have been requested but not yet picked up.
+    pub fn pending(&self) -> usize { self.pending }
+}
+
+// takes requests off the shared queue until the loader is dropped
+fn worker(queue: Arc<Mutex<Receiver<(TextureId, String)>>>, done: Sender<Loaded>) {
+    loop {
+        let request = match queue.lock() {
+            Ok(queue) => queue.recv(),
+            Err(_)    => return,
+        };
+
+        let (id, path) = match request { Ok(request) => request, Err(_) => return };
+        let pixels = read_image(&path, Alpha::Straight);
+
+        if done.send(Loaded { id: id, path: path, pixels: pixels }).is_err() { return }
+    }
+}
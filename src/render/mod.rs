@@ -0,0 +1,787 @@
+use std::mem;
+use std::sync::Arc;
+
+use glium::{self, Surface, Texture2d, VertexBuffer};
+use glium::backend::glutin_backend::GlutinFacade;
+use glium::draw_parameters::DrawParameters;
+use glium::index::{NoIndices, PrimitiveType};
+use glium::texture::{MipmapsOption, TextureCreationError};
+
+use error::{Error, Result};
+use units::drawing::{RGBA, V3};
+
+use self::assets::Registry;
+use self::atlas::AtlasPacker;
+use self::decode::{read_image, Alpha};
+use self::loader::Loader;
+#[cfg(feature = "dev")]
+use self::reload::Watcher;
+use self::shaders::Shaders;
+use self::text::GlyphCache;
+
+pub use self::animation::{Animation, AnimationPlayer, Playback};
+pub use self::assets::{TextureHandle, TextureId};
+pub use self::atlas::UvRect;
+pub use self::shaders::{Blending, Params, ShaderId};
+pub use self::sheet::SpriteSheet;
+pub use self::text::{Align, Text};
+
+pub mod animation;
+pub mod assets;
+pub mod atlas;
+pub mod decode;
+mod loader;
+#[cfg(feature = "dev")]
+mod reload;
+pub mod sheet;
+pub mod shaders;
+pub mod text;
+
+// NOTE: these are not necessarily hard limits, though exceeding them
+//       will at best cause reallocation on the heap, at worst this will
+//       blow up OpenGL.
+
+// renderer settings
+pub static MAX_PARTICLES: usize = 256;
+pub static MAX_RECTS: usize = 768;
+pub static MAX_PAGES: usize = 16;
+
+fn gen_checkers(buf: &mut Vec<Vec<RGBA>>) {
+    for sh in 0..8 {
+        for sw in 0..8 {
+            let color = (sh + sw) % 2 == 0;
+            let y = sh * 32;
+            let x = sw * 32;
+
+            // draw boxes
+            for row in y..(y+32) {
+                for col in x..(x+32) {
+                    buf[row][col] = if color { (255,0,255,255) } else { (0,0,0,255) };
+                }
+            }
+
+        }
+    }
+}
+
+/// Glutin renderer implementation
+/// Stores a reference to the glutin window along w/ its shader programs
+/// and GL parameters. This rendergroup is only valid in the
+/// thread which owns this GPU context, and must not outlive this context.
+///
+/// Every job is drawn as quads: plotted as triangles (no index buffer,
+/// 6 verts per quad) w/ UV coords into an atlas page, through whichever
+/// program the job asks for. (see `shaders::Shaders`)
+///
+/// Textures are packed into a handful of shared atlas pages, and consecutive
+/// jobs which share a page, program & uniforms are batched: their quads are
+/// transformed (& colored) on the CPU and collected into `batch`, which is
+/// uploaded & drawn in one call once any of those change (or the batch
+/// fills up.)
+///
+/// Text is drawn from a glyph cache which shares the atlas w/ everything
+/// else, so a string is just a batch of quads like any other job.
+///
+/// Textures are looked up by id when drawn, any which are missing (the
+/// file could not be read, it was unloaded, etc.) are drawn w/ a pink &
+/// black checkerboard instead.
+///
+/// Images can also be loaded in the background (`load_image_async()`),
+/// these draw as the checkerboard until they have been decoded, and are
+/// uploaded at the start of the next frame after that.
+///
+/// W/ the `dev` feature enabled, images & shaders are reloaded whenever
+/// they change on disk. (see `reload::Watcher`)
+///
+pub struct RenderGroup<'scn> {
+    gpu:    &'scn GlutinFacade,
+    config:  &'scn DrawParameters<'scn>,
+    vbuf:    VertexBuffer<V3>,
+    shaders: Shaders,
+
+    pages:    Vec<Texture2d>,
+    packer:   AtlasPacker,
+    glyphs:   Option<GlyphCache>,
+    textures: Registry<Location>,
+    fallback: Location,
+    fallback_id: TextureId,
+    loader:   Loader,
+    #[cfg(feature = "dev")]
+    watcher:  Watcher,
+
+    batch:     Vec<V3>,
+    batch_key: Option<Pass>,
+}
+
+// what a batch of quads is drawn w/, quads can only share a draw call if
+// all of this matches.
+#[derive(Copy, Clone, PartialEq)]
+struct Pass {
+    page:   usize,
+    shader: ShaderId,
+    params: Params,
+}
+
+impl<'scn> RenderGroup<'scn> {
+    pub fn new(display: &'scn GlutinFacade, draw_params: &'scn DrawParameters<'scn>) -> Result<RenderGroup<'scn>> {
+        let mut textures = Registry::new();
+        let fallback_id  = textures.insert(None, Location { page: 0, uv: UvRect::whole(), region: None });
+
+        let mut group = RenderGroup {
+            config: draw_params,
+            gpu:   display,
+            vbuf:    VertexBuffer::empty_dynamic(display, MAX_RECTS * 6)?,
+            shaders: Shaders::new(display)?,
+
+            pages:    Vec::with_capacity(MAX_PAGES),
+            packer:   AtlasPacker::new(),
+            glyphs:   None,
+            textures: textures,
+            fallback: Location { page: 0, uv: UvRect::whole(), region: None },
+            fallback_id: fallback_id,
+            loader:   Loader::new()?,
+            #[cfg(feature = "dev")]
+            watcher:  Watcher::new(),
+
+            batch:     Vec::with_capacity(MAX_RECTS * 6),
+            batch_key: None,
+        };
+
+        let (page, x, y) = group.reserve(text::GLYPH_CACHE_SIZE, text::GLYPH_CACHE_SIZE)?;
+        let page_size    = group.packer.page_size(page);
+        group.glyphs     = Some(GlyphCache::new(page, (x, y), page_size)?);
+
+        // the fallback is never unloaded, so it doesn't need to remember its room
+        let mut checkers = vec![vec![(0u8, 0u8, 0u8, 0u8); 256]; 256];
+        gen_checkers(&mut checkers);
+        group.fallback = Location { region: None, .. group.place(checkers)? };
+        if let Some(location) = group.textures.get_mut(fallback_id) { *location = group.fallback; }
+
+        #[cfg(feature = "dev")]
+        group.watch_shaders();
+
+        Ok(group)
+    }
+
+    /// Draws every job in `draw_list`, jobs in world space are seen
+    /// through `view`. (see `Camera::view()`) Drawing stops at the first
+    /// batch the GPU rejects, the rest of the frame is skipped.
+    pub fn draw<S: Surface>(&mut self, draw_list: &[RenderJob], view: &View, frame: &mut S) -> Result<()> {
+         let screen = View::screen();
+
+         #[cfg(feature = "dev")]
+         self.reload_changed();
+         self.finish_loads();
+
+         // glyphs for the whole frame are cached up front, so caching the
+         // glyphs of one string can't evict those of another mid-frame.
+         let mut layouts = self.layout_text(draw_list).into_iter();
+
+         for job in draw_list {
+            match *job {
+                RenderJob::ClearDepth(depth)    => { self.flush(frame)?; frame.clear_depth(depth) },
+                RenderJob::ClearScreen(r,g,b,a) => { self.flush(frame)?; frame.clear_color(r,g,b,a) },
+
+                RenderJob::Draw(ref rect) => {
+                    let view = if rect.space == Space::World { view } else { &screen };
+                    let (page, uv) = self.resolve(rect.texture);
+                    let pass = Pass { page: page, shader: rect.shader, params: rect.params };
+                    self.push_rect(frame, pass, uv, rect.tint, rect.dim, &rect.transform, view)?;
+                },
+
+                RenderJob::DrawMany(ref batch) => {
+                    let view = if batch.space == Space::World { view } else { &screen };
+                    let (page, uv) = self.resolve(batch.texture);
+                    let pass = Pass { page: page, shader: batch.shader, params: batch.params };
+                    for (idx, dim) in batch.rects.iter().enumerate() {
+                        let color = batch.colors.as_ref().and_then(|colors| colors.get(idx));
+                        let tint  = color.map_or(batch.tint, |&color| modulate(batch.tint, color));
+                        self.push_rect(frame, pass, uv, tint, *dim, &batch.transform, view)?;
+                    }
+                },
+
+                RenderJob::Fill(ref rect) => {
+                    let view = if rect.space == Space::World { view } else { &screen };
+                    let pass = self.solid_pass();
+                    self.push_rect(frame, pass, UvRect::whole(), rect.color, rect.dim, &rect.transform, view)?;
+                },
+
+                RenderJob::FillMany(ref batch) => {
+                    let view = if batch.space == Space::World { view } else { &screen };
+                    let pass = self.solid_pass();
+                    for dim in batch.rects.iter() {
+                        self.push_rect(frame, pass, UvRect::whole(), batch.color, *dim, &batch.transform, view)?;
+                    }
+                },
+
+                RenderJob::DrawText(ref text) => {
+                    let glyphs = layouts.next().unwrap_or_else(Vec::new);
+                    self.push_text(frame, text, &glyphs)?;
+                },
+            }
+        }
+
+        self.flush(frame)
+    }
+
+    /// Compiles a program from the shaders at `vertex` & `fragment`, which
+    /// jobs can then be drawn w/. (see `TexRect::shader()`) The program must
+    /// declare the uniforms in `params`, see `Params` for details.
+    #[allow(dead_code)]
+    pub fn load_shader(&mut self, vertex: &str, fragment: &str, blending: Blending, params: Params) -> Result<ShaderId> {
+        let id = self.shaders.load(self.gpu, vertex, fragment, blending, params)?;
+
+        #[cfg(feature = "dev")]
+        self.watch_shaders();
+
+        Ok(id)
+    }
+
+    // uploads any images which have been decoded in the background, those
+    // which failed keep drawing as whatever they drew as before.
+    fn finish_loads(&mut self) {
+        if self.loader.pending() == 0 { return }
+
+        for loaded in self.loader.finished() {
+            // nothing to do if it was released while loading
+            if self.textures.refs(loaded.id) == 0 { continue }
+
+            let (id, path) = (loaded.id, loaded.path);
+            match loaded.pixels.and_then(|buf| self.place(buf)) {
+                Ok(location) => {
+                    let old = self.textures.get_mut(id).map(|slot| mem::replace(slot, location));
+                    if let Some(old) = old { self.free(old); }
+                },
+                Err(err) => {
+                    // a reload which fails leaves the previous image in place
+                    let reloading = self.textures.get(id).map_or(false, |slot| slot.region.is_some());
+                    let keeping   = if reloading { "keeping the previous image" } else { "using fallback" };
+                    println!("could not load texture, {}: {}", keeping, Error::asset(&path, err));
+                },
+            }
+        }
+    }
+
+    // gives the room taken by a texture back to the atlas
+    fn free(&mut self, location: Location) {
+        let (x, y, w, h) = match location.region { Some(region) => region, None => return };
+
+        // clear the old image out, so it can't bleed into whatever moves in
+        let pad   = atlas::PADDING;
+        let blank = vec![vec![(0u8, 0u8, 0u8, 0u8); (w + (pad * 2)) as usize]; (h + (pad * 2)) as usize];
+        self.pages[location.page].write(glium::Rect { left: x - pad, bottom: y - pad, width: w + (pad * 2), height: h + (pad * 2) }, blank);
+        self.packer.release(location.page, x, y, w, h);
+    }
+
+    // the atlas page holding `texture`, & the part of that page it covers
+    fn resolve(&self, texture: TextureHandle) -> (usize, UvRect) {
+        let location = self.textures.get(texture.id).unwrap_or(&self.fallback);
+        let (page_w, page_h) = self.packer.page_size(location.page);
+
+        // the inset comes last, so filtering stays inside each frame of a sheet
+        let uv = location.uv.map(texture.uv).inset(0.5 / page_w as f32, 0.5 / page_h as f32);
+        (location.page, uv)
+    }
+
+    // solid quads don't sample their page, so they stay on whichever page
+    // is already bound rather than breaking up the batch.
+    fn solid_pass(&self) -> Pass {
+        Pass { page: self.batch_key.map_or(0, |pass| pass.page), shader: shaders::SOLID, params: Params::new() }
+    }
+
+    // queues a quad, first drawing the current batch if it can't be extended
+    fn push_rect<S: Surface>(&mut self, frame: &mut S, pass: Pass, uv: UvRect, color: Tint, dim: Rect, transform: &Transform, view: &View) -> Result<()> {
+        let batch_full = self.batch.len() + 6 > self.vbuf.len();
+        if self.batch_key != Some(pass) || batch_full { self.flush(frame)?; }
+
+        self.batch_key = Some(pass);
+        push_quad(&mut self.batch, dim, uv, color, transform, view);
+        Ok(())
+    }
+
+    // finds room for a `w` x `h` image in the atlas, allocating a new page
+    // if need be, and returns the page & the image's bottom-left corner.
+    fn reserve(&mut self, w: u32, h: u32) -> Result<(usize, u32, u32)> {
+        let placement = match self.packer.pack(w, h) {
+            Some(placement) => placement,
+            None => return Err(Error::Texture(TextureCreationError::DimensionsNotSupported)),
+        };
+
+        // pages start out transparent, so the padding between images is too.
+        // (images are drawn at about their own size, so pages skip mipmaps:
+        // they would blend neighbouring images together anyway.)
+        if let Some((page_w, page_h)) = placement.new_page {
+            let blank = vec![vec![(0u8, 0u8, 0u8, 0u8); page_w as usize]; page_h as usize];
+            let page  = match Texture2d::with_mipmaps(self.gpu, blank, MipmapsOption::NoMipmap) {
+                Ok(page) => page,
+                Err(err) => { self.packer.discard_page(placement.page); return Err(Error::from(err)) },
+            };
+
+            self.pages.push(page);
+        }
+
+        Ok((placement.page, placement.x + atlas::PADDING, placement.y + atlas::PADDING))
+    }
+
+    // uploads an image into the atlas
+    fn place(&mut self, buf: Vec<Vec<RGBA>>) -> Result<Location> {
+        let (w, h) = (buf.first().map_or(0, |row| row.len()) as u32, buf.len() as u32);
+        let (page, x, y) = self.reserve(w, h)?;
+        let (page_w, page_h) = self.packer.page_size(page);
+
+        self.pages[page].write(glium::Rect { left: x, bottom: y, width: w, height: h }, buf);
+        Ok(Location { page: page, uv: UvRect::texels(x, y, w, h, page_w, page_h), region: Some((x, y, w, h)) })
+    }
+
+    // uploads & draws every quad in the batch w/ a single call, the batch
+    // is emptied even if the draw fails.
+    fn flush<S: Surface>(&mut self, frame: &mut S) -> Result<()> {
+        let pass = match self.batch_key.take() {
+            Some(pass) if !self.batch.is_empty() => pass,
+            _ => { self.batch.clear(); return Ok(()) },
+        };
+
+        let drawn = self.draw_batch(frame, &pass);
+        self.batch.clear();
+        drawn
+    }
+
+    fn draw_batch<S: Surface>(&mut self, frame: &mut S, pass: &Pass) -> Result<()> {
+        // `push_rect()` flushes before the batch outgrows the buffer
+        let verts = self.batch.len();
+        {
+            self.vbuf.invalidate();
+            let vbuf = self.vbuf.slice_mut(0..verts).ok_or(Error::Batch(verts))?;
+            vbuf.write(&self.batch);
+        }
+
+        let shader   = self.shaders.get(pass.shader);
+        let params   = DrawParameters { blend: shader.blend(self.config.blend), .. self.config.clone() };
+        let uniforms = shader.bindings(&self.pages[pass.page], &pass.params);
+
+        frame.draw(self.vbuf.slice(0..verts).ok_or(Error::Batch(verts))?,
+                   NoIndices(PrimitiveType::TrianglesList),
+                   &shader.program,
+                   &uniforms,
+                   &params)?;
+
+        Ok(())
+    }
+}
+
+/// Anything which can take ownership of pixel data & hand back a handle
+/// to it that the renderer understands. Entities load their assets through
+/// this so they can be built with or without a display attached.
+///
+/// Textures loaded from a file are shared: loading a path again hands back
+/// the texture which is already loaded, and adds a reference to it. Each
+/// load (or store) should eventually be paired w/ a `release()`.
+pub trait TextureStore {
+    fn load_image(&mut self, path: &str) -> Result<TextureHandle>;
+
+    /// Starts loading the image at `path` & returns a handle to it right
+    /// away, which draws as the placeholder until the image is ready. Errors
+    /// are reported when the load finishes, the placeholder is kept.
+    fn load_image_async(&mut self, path: &str) -> TextureHandle;
+
+    #[allow(dead_code)]
+    fn store_texture(&mut self, buf: Vec<Vec<RGBA>>) -> Result<TextureHandle>;
+
+    /// Drops a reference to `texture`, unloading it once there are none left.
+    #[allow(dead_code)]
+    fn release(&mut self, texture: TextureHandle);
+
+    /// A placeholder to draw in place of a texture which failed to load.
+    fn fallback(&self) -> TextureHandle;
+
+    /// Loads the image at `path`, falling back to the placeholder (and
+    /// reporting why) if it can't be loaded.
+    #[allow(dead_code)]
+    fn load_image_or_fallback(&mut self, path: &str) -> TextureHandle {
+        self.load_image(path).unwrap_or_else(|err| {
+            println!("could not load texture, using fallback: {}", err);
+            self.fallback()
+        })
+    }
+
+    /// Stores `buf`, falling back to the placeholder (and reporting why)
+    /// if it can't be stored.
+    #[allow(dead_code)]
+    fn store_texture_or_fallback(&mut self, buf: Vec<Vec<RGBA>>) -> TextureHandle {
+        self.store_texture(buf).unwrap_or_else(|err| {
+            println!("could not store texture, using fallback: {}", err);
+            self.fallback()
+        })
+    }
+}
+
+// where a texture lives in the atlas, & the room it was given there
+// (none for stand-ins which share the fallback texture.)
+#[derive(Copy, Clone, Debug)]
+struct Location {
+    page:   usize,
+    uv:     UvRect,
+    region: Option<(u32, u32, u32, u32)>,
+}
+
+impl<'scn> TextureStore for RenderGroup<'scn> {
+    /// Loads the image at `path` into the atlas, see `decode::read_image()`
+    /// for the formats which are understood.
+    fn load_image(&mut self, path: &str) -> Result<TextureHandle> {
+        if let Some(id) = self.textures.acquire(path) { return Ok(TextureHandle::whole(id)) }
+
+        let location = read_image(path, Alpha::Straight).and_then(|buf| self.place(buf))
+                                                        .map_err(|err| Error::asset(path, err))?;
+
+        #[cfg(feature = "dev")]
+        self.watcher.watch(path);
+
+        Ok(TextureHandle::whole(self.textures.insert(Some(path), location)))
+    }
+
+    /// Queues the image at `path` to be decoded on a worker thread, the
+    /// handle draws as the fallback until it has been uploaded.
+    fn load_image_async(&mut self, path: &str) -> TextureHandle {
+        if let Some(id) = self.textures.acquire(path) { return TextureHandle::whole(id) }
+
+        let id = self.textures.insert(Some(path), self.fallback);
+        self.loader.request(id, path);
+
+        #[cfg(feature = "dev")]
+        self.watcher.watch(path);
+        TextureHandle::whole(id)
+    }
+
+    // TODO: generic source? slice? etc.
+    // TODO: enumerated color formats?
+    /// Packs a 2D pixel buffer into one of the atlas pages and returns a
+    /// handle to it which can be used to instruct the renderer to bank-in
+    /// that page (and sample the right part of it) for a program pass.
+    fn store_texture(&mut self, buf: Vec<Vec<RGBA>>) -> Result<TextureHandle> {
+        let location = self.place(buf)?;
+        Ok(TextureHandle::whole(self.textures.insert(None, location)))
+    }
+
+    fn release(&mut self, texture: TextureHandle) {
+        if let Some(location) = self.textures.release(texture.id) { self.free(location); }
+    }
+
+    fn fallback(&self) -> TextureHandle { TextureHandle::whole(self.fallback_id) }
+}
+
+/// Hands out texture handles without reading or uploading anything.
+/// Used to build entities when the game is running without a display.
+pub struct NullTextures { textures: Registry<()>, fallback_id: TextureId }
+
+impl NullTextures {
+    pub fn new() -> Self {
+        let mut textures = Registry::new();
+        let fallback_id  = textures.insert(None, ());
+        NullTextures { textures: textures, fallback_id: fallback_id }
+    }
+}
+
+impl TextureStore for NullTextures {
+    fn load_image(&mut self, path: &str) -> Result<TextureHandle> {
+        let id = self.textures.acquire(path).unwrap_or_else(|| self.textures.insert(Some(path), ()));
+        Ok(TextureHandle::whole(id))
+    }
+
+    fn load_image_async(&mut self, path: &str) -> TextureHandle {
+        self.load_image(path).unwrap_or_else(|_| self.fallback())
+    }
+
+    fn store_texture(&mut self, _buf: Vec<Vec<RGBA>>) -> Result<TextureHandle> {
+        Ok(TextureHandle::whole(self.textures.insert(None, ())))
+    }
+
+    fn release(&mut self, texture: TextureHandle) { self.textures.release(texture.id); }
+
+    fn fallback(&self) -> TextureHandle { TextureHandle::whole(self.fallback_id) }
+}
+
+// renderer primitives below here ...
+
+#[derive(Copy,Clone,Debug)]
+pub struct Rect {
+    pub x: f32, pub y: f32, pub z: f32,
+    pub w: f32, pub h: f32,
+}
+
+/// Which coordinate system a job's rects are measured in.
+#[allow(dead_code)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Space {
+    /// World coordinates, as seen through the camera.
+    World,
+
+    /// Unit coordinates fixed to the window, (0,0) is the bottom-left
+    /// corner and (1,1) is the top-right. (e.g: for the HUD)
+    Screen,
+}
+
+/// A 2D affine transform which takes a point to GL's -1..1 space, this
+/// is the combined view & projection for a coordinate space.
+#[derive(Copy, Clone, Debug)]
+pub struct View {
+    pub matrix: [[f32; 2]; 2],
+    pub offset: [f32; 2],
+}
+
+impl View {
+    /// The view for `Space::Screen`.
+    pub fn screen() -> Self {
+        View { matrix: [[2.0, 0.0], [0.0, 2.0]], offset: [-1.0, -1.0] }
+    }
+
+    /// Transforms a point into GL's -1..1 space.
+    pub fn apply(&self, x: f32, y: f32) -> (f32, f32) {
+        let m = &self.matrix;
+        ((m[0][0] * x) + (m[0][1] * y) + self.offset[0],
+         (m[1][0] * x) + (m[1][1] * y) + self.offset[1])
+    }
+}
+
+/// Places a job's rects on screen. Rects are first scaled & rotated
+/// (counter-clockwise, in radians) about `origin`, then moved by `position`.
+/// All of these are measured in the same space as the rects themselves.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Transform {
+    pub position: [f32; 2],
+    pub rotation: f32,
+    pub scale:    [f32; 2],
+    pub origin:   [f32; 2],
+}
+
+impl Transform {
+    /// A transform which leaves rects where they are.
+    pub fn identity() -> Self {
+        Transform { position: [0.0, 0.0], rotation: 0.0, scale: [1.0, 1.0], origin: [0.0, 0.0] }
+    }
+
+    /// Rotates rects by `theta` radians about `(ox, oy)`.
+    pub fn rotate_about(theta: f32, ox: f32, oy: f32) -> Self {
+        Transform { rotation: theta, origin: [ox, oy], .. Transform::identity() }
+    }
+
+    /// Transforms a point in unit space.
+    fn apply(&self, x: f32, y: f32) -> (f32, f32) {
+        let (x, y) = ((x - self.origin[0]) * self.scale[0], (y - self.origin[1]) * self.scale[1]);
+        let (sin, cos) = self.rotation.sin_cos();
+
+        (self.origin[0] + self.position[0] + (x * cos) - (y * sin),
+         self.origin[1] + self.position[1] + (x * sin) + (y * cos))
+    }
+}
+
+/// Color multiplied w/ each texel of a job, as RGBA from 0.0 to 1.0.
+/// Lowering the alpha fades the job out.
+pub type Tint = [f32; 4];
+
+/// Tint which leaves a texture's colors untouched.
+pub static WHITE: Tint = [1.0, 1.0, 1.0, 1.0];
+
+// one tint applied on top of another
+fn modulate(a: Tint, b: Tint) -> Tint {
+    [a[0] * b[0], a[1] * b[1], a[2] * b[2], a[3] * b[3]]
+}
+
+/// Appends a quad textured w/ the `uv` part of a page & colored `color`
+/// to `verts`, as a pair of triangles.
+fn push_quad(verts: &mut Vec<V3>, dim: Rect, uv: UvRect, color: Tint, transform: &Transform, view: &View) {
+    let (x1, y1) = transform.apply(dim.x,         dim.y);
+    let (x2, y2) = transform.apply(dim.x + dim.w, dim.y);
+    let (x3, y3) = transform.apply(dim.x + dim.w, dim.y + dim.h);
+    let (x4, y4) = transform.apply(dim.x,         dim.y + dim.h);
+
+    let (p1, p2) = (view.apply(x1, y1), view.apply(x2, y2));
+    let (p3, p4) = (view.apply(x3, y3), view.apply(x4, y4));
+
+    verts.extend_from_slice(&[
+        V3 { pos: [p1.0, p1.1, dim.z], uv: [uv.u1, uv.v1], color: color },
+        V3 { pos: [p2.0, p2.1, dim.z], uv: [uv.u2, uv.v1], color: color },
+        V3 { pos: [p3.0, p3.1, dim.z], uv: [uv.u2, uv.v2], color: color },
+
+        V3 { pos: [p1.0, p1.1, dim.z], uv: [uv.u1, uv.v1], color: color },
+        V3 { pos: [p4.0, p4.1, dim.z], uv: [uv.u1, uv.v2], color: color },
+        V3 { pos: [p3.0, p3.1, dim.z], uv: [uv.u2, uv.v2], color: color },
+    ]);
+}
+
+/// A single textured rect.
+#[derive(Copy,Clone)]
+pub struct TexRect {
+    texture: TextureHandle,
+    dim: Rect,
+    transform: Transform,
+    tint: Tint,
+    shader: ShaderId,
+    params: Params,
+    space: Space,
+}
+
+impl TexRect {
+    pub fn from(texture: TextureHandle, x: f32, y: f32, z: f32, w: f32, h: f32) -> TexRect {
+        TexRect {
+            texture:    texture,
+            dim:        Rect { x: x, y: y, z: z, w: w, h: h },
+            transform:  Transform::identity(),
+            tint:       WHITE,
+            shader:     shaders::TINTED,
+            params:     Params::new(),
+            space:      Space::World,
+        }
+    }
+
+    pub fn transform(mut self, transform: Transform) -> Self { self.transform = transform; self }
+
+    #[allow(dead_code)]
+    pub fn tint(mut self, tint: Tint) -> Self { self.tint = tint; self }
+
+    /// Draws the rect w/ program `shader` rather than `shaders::TINTED`.
+    pub fn shader(mut self, shader: ShaderId) -> Self { self.shader = shader; self }
+
+    /// Sets the uniforms `shader` declares, on top of `tex`.
+    #[allow(dead_code)]
+    pub fn params(mut self, params: Params) -> Self { self.params = params; self }
+
+    #[allow(dead_code)]
+    pub fn space(mut self, space: Space) -> Self { self.space = space; self }
+}
+
+/// Many rects sharing one texture, transform & tint.
+///
+/// Each rect can also be given a tint of its own (see `TexBatch::colors()`)
+/// which is applied on top of the batch's, e.g: to fade particles out.
+///
+/// The rects are shared w/ whoever built the batch rather than copied,
+/// they can keep refilling the same buffer through `Arc::make_mut()`.
+#[derive(Clone)]
+pub struct TexBatch {
+    texture: TextureHandle,
+    rects: Arc<Vec<Rect>>,
+    colors: Option<Arc<Vec<Tint>>>,
+    transform: Transform,
+    tint: Tint,
+    shader: ShaderId,
+    params: Params,
+    space: Space,
+}
+
+impl TexBatch {
+    pub fn from(texture: TextureHandle, rects: Arc<Vec<Rect>>) -> TexBatch {
+        TexBatch {
+            texture:    texture,
+            rects:      rects,
+            colors:     None,
+            transform:  Transform::identity(),
+            tint:       WHITE,
+            shader:     shaders::TINTED,
+            params:     Params::new(),
+            space:      Space::World,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn transform(mut self, transform: Transform) -> Self { self.transform = transform; self }
+
+    #[allow(dead_code)]
+    pub fn tint(mut self, tint: Tint) -> Self { self.tint = tint; self }
+
+    /// Tints rect `n` w/ `colors[n]` as well, rects w/o a color of their
+    /// own only get the batch's tint.
+    pub fn colors(mut self, colors: Arc<Vec<Tint>>) -> Self { self.colors = Some(colors); self }
+
+    /// Draws the rects w/ program `shader` rather than `shaders::TINTED`.
+    #[allow(dead_code)]
+    pub fn shader(mut self, shader: ShaderId) -> Self { self.shader = shader; self }
+
+    /// Sets the uniforms `shader` declares, on top of `tex`.
+    #[allow(dead_code)]
+    pub fn params(mut self, params: Params) -> Self { self.params = params; self }
+
+    #[allow(dead_code)]
+    pub fn space(mut self, space: Space) -> Self { self.space = space; self }
+}
+
+/// A single rect filled w/ a solid color, no texture needed.
+#[derive(Copy,Clone)]
+pub struct ColorRect {
+    dim: Rect,
+    transform: Transform,
+    color: Tint,
+    space: Space,
+}
+
+impl ColorRect {
+    #[allow(dead_code)]
+    pub fn from(color: Tint, x: f32, y: f32, z: f32, w: f32, h: f32) -> ColorRect {
+        ColorRect {
+            dim:        Rect { x: x, y: y, z: z, w: w, h: h },
+            transform:  Transform::identity(),
+            color:      color,
+            space:      Space::World,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn transform(mut self, transform: Transform) -> Self { self.transform = transform; self }
+
+    #[allow(dead_code)]
+    pub fn space(mut self, space: Space) -> Self { self.space = space; self }
+}
+
+/// Many rects filled w/ one solid color, sharing a transform.
+#[derive(Clone)]
+pub struct ColorBatch {
+    rects: Arc<Vec<Rect>>,
+    transform: Transform,
+    color: Tint,
+    space: Space,
+}
+
+impl ColorBatch {
+    pub fn from(color: Tint, rects: Arc<Vec<Rect>>) -> ColorBatch {
+        ColorBatch {
+            rects:      rects,
+            transform:  Transform::identity(),
+            color:      color,
+            space:      Space::World,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn transform(mut self, transform: Transform) -> Self { self.transform = transform; self }
+
+    #[allow(dead_code)]
+    pub fn space(mut self, space: Space) -> Self { self.space = space; self }
+}
+
+/// Something for the renderer to do. Each draw job carries everything
+/// needed to draw it, so jobs do not depend on the ones queued before
+/// them and can be reordered freely. (Aside from the clears.) Jobs are
+/// also `Send`, so they can be built on another thread.
+#[derive(Clone)]
+pub enum RenderJob {
+    ClearDepth(f32),
+    ClearScreen(f32, f32, f32, f32),
+
+    Draw(TexRect),
+    DrawMany(TexBatch),
+    DrawText(Text),
+
+    /// Solid color rects, drawn w/ `shaders::SOLID`.
+    #[allow(dead_code)]
+    Fill(ColorRect),
+    FillMany(ColorBatch),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RenderJob;
+
+    fn assert_send<T: Send>() {}
+
+    #[test]
+    fn jobs_can_cross_threads() { assert_send::<RenderJob>(); }
+}
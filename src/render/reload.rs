@@ -0,0 +1,75 @@
+use std::collections::HashMap;
+use std::fs;
+use std::time::{Duration, Instant, SystemTime};
+
+use render::RenderGroup;
+
+// how often watched files are checked for changes
+static POLL_INTERVAL_MS: u64 = 500;
+
+/// Notices when files change on disk, by polling their modification times.
+///
+/// Only files which have been loaded are watched, rather than everything
+/// under `assets/`, and checking a few dozen of them twice a second is
+/// cheap enough that we don't need to ask the OS to notify us.
+pub struct Watcher {
+    files:     HashMap<String, Option<SystemTime>>,
+    last_poll: Instant,
+}
+
+impl Watcher {
+    pub fn new() -> Self {
+        Watcher { files: HashMap::new(), last_poll: Instant::now() }
+    }
+
+    /// Starts watching `path`, if it isn't watched already.
+    pub fn watch(&mut self, path: &str) {
+        if self.files.contains_key(path) { return }
+        self.files.insert(path.to_string(), modified(path));
+    }
+
+    /// Every watched file which has been modified since the last poll, or
+    /// nothing if it isn't time to poll again yet.
+    pub fn changed(&mut self) -> Vec<String> {
+        if self.last_poll.elapsed() < Duration::from_millis(POLL_INTERVAL_MS) { return vec![] }
+        self.last_poll = Instant::now();
+
+        let mut changed = vec![];
+        for (path, seen) in &mut self.files {
+            // files which are missing (e.g: mid-save) are checked again later
+            let modified = modified(path);
+            if modified.is_some() && modified != *seen {
+                *seen = modified;
+                changed.push(path.clone());
+            }
+        }
+
+        changed
+    }
+}
+
+fn modified(path: &str) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+impl<'scn> RenderGroup<'scn> {
+    // watches the source of every program, including any loaded since last time
+    pub(super) fn watch_shaders(&mut self) {
+        for path in self.shaders.paths() { self.watcher.watch(&path); }
+    }
+
+    // recompiles any programs whose source changed, & queues changed images
+    // to be loaded again into their existing textures.
+    pub(super) fn reload_changed(&mut self) {
+        let shader_paths = self.shaders.paths();
+
+        for path in self.watcher.changed() {
+            if shader_paths.contains(&path) {
+                self.shaders.reload(self.gpu, &path);
+            } else if let Some(id) = self.textures.find(&path) {
+                println!("reloading texture: {}", path);
+                self.loader.request(id, &path);
+            }
+        }
+    }
+}
@@ -0,0 +1,384 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use glium::{Blend, BlendingFunction, LinearBlendingFactor, Program, Texture2d};
+use glium::backend::Facade;
+use glium::uniforms::{MinifySamplerFilter, SamplerBehavior, UniformType, UniformValue, Uniforms};
+
+use error::{Error, Result};
+
+// built-in programs, all of which share the vertex shader
+static SHD_SQUARE_VTX: &'static str = include_str!("../../assets/shaders/square.glsv");
+static SHD_SQUARE_FRG: &'static str = include_str!("../../assets/shaders/square.glsf");
+static SHD_SOLID_FRG:  &'static str = include_str!("../../assets/shaders/solid.glsf");
+
+// where the built-in programs live on disk, for reloading them (see `dev` feature)
+static SHD_SQUARE_VTX_PATH: &'static str = "assets/shaders/square.glsv";
+static SHD_SQUARE_FRG_PATH: &'static str = "assets/shaders/square.glsf";
+static SHD_SOLID_FRG_PATH:  &'static str = "assets/shaders/solid.glsf";
+
+// number of extra uniforms a job can set, see `Params`
+const MAX_PARAMS: usize = 4;
+
+// set once a job has tried to set too many uniforms, so it's only reported once
+static PARAMS_OVERFLOWED: AtomicBool = AtomicBool::new(false);
+
+/// Identifies a program held by the renderer, see `Shaders`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ShaderId(usize);
+
+/// Textured quads, multiplied by their color. (the default)
+pub static TINTED: ShaderId = ShaderId(0);
+
+/// Quads filled w/ their color, their texture is ignored.
+/// (see `RenderJob::Fill`)
+pub static SOLID: ShaderId = ShaderId(1);
+
+/// Like `TINTED`, but brightens whatever is underneath rather than
+/// covering it. (e.g: glows & flashes)
+pub static ADDITIVE: ShaderId = ShaderId(2);
+
+/// How a program's output is combined w/ what has already been drawn.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Blending {
+    /// Whatever blending the renderer was created w/, usually alpha blending.
+    Default,
+
+    /// Colors are scaled by their alpha & added onto the frame.
+    Additive,
+}
+
+impl Blending {
+    fn blend(&self, default: Blend) -> Blend {
+        match *self {
+            Blending::Default  => default,
+            Blending::Additive => Blend {
+                color: BlendingFunction::Addition {
+                    source:      LinearBlendingFactor::SourceAlpha,
+                    destination: LinearBlendingFactor::One,
+                },
+                alpha: BlendingFunction::Addition {
+                    source:      LinearBlendingFactor::Zero,
+                    destination: LinearBlendingFactor::One,
+                },
+                constant_value: (0.0, 0.0, 0.0, 0.0),
+            },
+        }
+    }
+}
+
+/// A value for one of a program's uniforms.
+#[allow(dead_code)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Uniform {
+    Float(f32),
+    Vec2([f32; 2]),
+    Vec3([f32; 3]),
+    Vec4([f32; 4]),
+}
+
+impl Uniform {
+    // what a program declares a uniform holding this value as
+    fn ty(&self) -> UniformType {
+        match *self {
+            Uniform::Float(_) => UniformType::Float,
+            Uniform::Vec2(_)  => UniformType::FloatVec2,
+            Uniform::Vec3(_)  => UniformType::FloatVec3,
+            Uniform::Vec4(_)  => UniformType::FloatVec4,
+        }
+    }
+}
+
+/// The uniforms a job sets for its program, on top of `tex` which the
+/// renderer always sets.
+///
+/// A program can declare up to `MAX_PARAMS` uniforms of its own, which are
+/// given to `Shaders::load()` as a `Params` of their own: the names & types
+/// the program must declare, w/ the values used when a job doesn't set
+/// them. Values a job sets which aren't in that block, or are of the wrong
+/// type, are ignored. Jobs are only batched together if they set the same
+/// values, so they're best used for things which change slowly.
+/// (e.g: how bright a glow is)
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Params {
+    values: [Option<(&'static str, Uniform)>; MAX_PARAMS],
+}
+
+impl Params {
+    pub fn new() -> Self { Params { values: [None; MAX_PARAMS] } }
+
+    /// Sets uniform `name`, replacing its old value if it was already set.
+    /// Setting more than `MAX_PARAMS` uniforms is a bug: debug builds panic,
+    /// release builds ignore the extra uniforms. (& say so the first time.)
+    #[allow(dead_code)]
+    pub fn set(mut self, name: &'static str, value: Uniform) -> Self {
+        match self.values.iter().position(|slot| slot.map_or(true, |(key, _)| key == name)) {
+            Some(slot) => self.values[slot] = Some((name, value)),
+            None => {
+                debug_assert!(false, "too many uniforms set for one job: {}", name);
+                if !PARAMS_OVERFLOWED.swap(true, Ordering::Relaxed) {
+                    println!("too many uniforms set for one job, ignoring: {}", name);
+                }
+            },
+        }
+
+        self
+    }
+
+    /// The value of uniform `name`, if it has been set.
+    pub fn get(&self, name: &str) -> Option<Uniform> {
+        self.values.iter()
+            .filter_map(|slot| slot.as_ref())
+            .find(|&&(key, _)| key == name)
+            .map(|&(_, value)| value)
+    }
+
+    // the values in `self` (a program's block) w/ any of `params` which fit it
+    fn bind(&self, params: &Params) -> Params {
+        let mut bound = *self;
+        for slot in bound.values.iter_mut() {
+            if let Some((name, ref mut value)) = *slot {
+                match params.get(name) {
+                    Some(param) if param.ty() == value.ty() => *value = param,
+                    _ => {},
+                }
+            }
+        }
+
+        bound
+    }
+}
+
+/// Everything a batch binds when it's drawn.
+pub struct Bindings<'a> {
+    pub texture: &'a Texture2d,
+    pub params:  Params,
+}
+
+impl<'a> Uniforms for Bindings<'a> {
+    fn visit_values<'b, F: FnMut(&str, UniformValue<'b>)>(&'b self, mut visit: F) {
+        // atlas pages have no mipmaps, so minifying must not ask for them
+        let sampler = SamplerBehavior { minify_filter: MinifySamplerFilter::Linear, ..Default::default() };
+        visit("tex", UniformValue::Texture2d(self.texture, Some(sampler)));
+
+        for &(name, value) in self.params.values.iter().filter_map(|slot| slot.as_ref()) {
+            visit(name, match value {
+                Uniform::Float(x) => UniformValue::Float(x),
+                Uniform::Vec2(v)  => UniformValue::Vec2(v),
+                Uniform::Vec3(v)  => UniformValue::Vec3(v),
+                Uniform::Vec4(v)  => UniformValue::Vec4(v),
+            });
+        }
+    }
+}
+
+/// A compiled program, the uniforms it takes, & where its source came from.
+pub struct Shader {
+    pub program:  Program,
+    pub blending: Blending,
+    params:       Params,
+
+    // only needed to reload the program (see `dev` feature)
+    #[cfg_attr(not(feature = "dev"), allow(dead_code))]
+    vertex:       String,
+    #[cfg_attr(not(feature = "dev"), allow(dead_code))]
+    fragment:     String,
+}
+
+impl Shader {
+    /// How to blend this program's output, given the renderer's own blending.
+    pub fn blend(&self, default: Blend) -> Blend { self.blending.blend(default) }
+
+    /// Everything to bind when drawing w/ this program, given the page
+    /// being drawn & the params set by the batch's jobs.
+    pub fn bindings<'a>(&self, texture: &'a Texture2d, params: &Params) -> Bindings<'a> {
+        Bindings { texture: texture, params: self.params.bind(params) }
+    }
+}
+
+/// Every program the renderer can draw w/.
+///
+/// All programs are fed the same verts: a position (already transformed),
+/// UVs into the atlas page being drawn & a color, six verts per quad. Jobs pick a
+/// program by id, the built-in ones (`TINTED`, `SOLID` & `ADDITIVE`) are
+/// always there, others are loaded from disk w/ `Shaders::load()`. A program
+/// is checked against the uniforms it was loaded w/ each time it's built.
+pub struct Shaders {
+    shaders: Vec<Shader>,
+}
+
+impl Shaders {
+    pub fn new<F: Facade>(display: &F) -> Result<Self> {
+        let built_in = [
+            (SHD_SQUARE_FRG, SHD_SQUARE_FRG_PATH, Blending::Default),
+            (SHD_SOLID_FRG,  SHD_SOLID_FRG_PATH,  Blending::Default),
+            (SHD_SQUARE_FRG, SHD_SQUARE_FRG_PATH, Blending::Additive),
+        ];
+
+        let mut shaders = Vec::with_capacity(built_in.len());
+        for &(fragment, fragment_path, blending) in built_in.iter() {
+            let program = Program::from_source(display, SHD_SQUARE_VTX, fragment, None)?;
+            check_uniforms(&Params::new(), &declared(&program))?;
+
+            shaders.push(Shader {
+                program:  program,
+                blending: blending,
+                params:   Params::new(),
+                vertex:   SHD_SQUARE_VTX_PATH.to_string(),
+                fragment: fragment_path.to_string(),
+            });
+        }
+
+        Ok(Shaders { shaders: shaders })
+    }
+
+    /// Compiles a program from the shaders at `vertex` & `fragment`, which
+    /// must declare exactly the uniforms in `params` (w/ the same types) & `tex`.
+    pub fn load<F: Facade>(&mut self, display: &F, vertex: &str, fragment: &str, blending: Blending, params: Params) -> Result<ShaderId> {
+        let program = compile(display, vertex, fragment, &params)?;
+
+        self.shaders.push(Shader {
+            program:  program,
+            blending: blending,
+            params:   params,
+            vertex:   vertex.to_string(),
+            fragment: fragment.to_string(),
+        });
+
+        Ok(ShaderId(self.shaders.len() - 1))
+    }
+
+    /// The program for `id`, programs the renderer doesn't know about
+    /// are drawn w/ `TINTED` instead.
+    pub fn get(&self, id: ShaderId) -> &Shader {
+        self.shaders.get(id.0).unwrap_or(&self.shaders[TINTED.0])
+    }
+
+    /// Every file a program was built from.
+    #[cfg(feature = "dev")]
+    pub fn paths(&self) -> Vec<String> {
+        let mut paths = vec![];
+        for shader in &self.shaders {
+            if !paths.contains(&shader.vertex)   { paths.push(shader.vertex.clone()); }
+            if !paths.contains(&shader.fragment) { paths.push(shader.fragment.clone()); }
+        }
+
+        paths
+    }
+
+    /// Recompiles every program built from `path`, any which fail to
+    /// compile (or no longer match their uniforms) keep their old program.
+    #[cfg(feature = "dev")]
+    pub fn reload<F: Facade>(&mut self, display: &F, path: &str) {
+        for shader in &mut self.shaders {
+            if shader.vertex != path && shader.fragment != path { continue }
+
+            match compile(display, &shader.vertex, &shader.fragment, &shader.params) {
+                Ok(program) => { shader.program = program; println!("reloaded shader: {}", path) },
+                Err(err) => println!("could not reload shader, keeping the old one: {}", err),
+            }
+        }
+    }
+}
+
+// reads & compiles a program from the shaders on disk, checking it against `params`
+fn compile<F: Facade>(display: &F, vertex: &str, fragment: &str, params: &Params) -> Result<Program> {
+    let vertex_src   = read_source(vertex).map_err(|err| Error::asset(vertex, err))?;
+    let fragment_src = read_source(fragment).map_err(|err| Error::asset(fragment, err))?;
+
+    let program = Program::from_source(display, &vertex_src, &fragment_src, None)?;
+    check_uniforms(params, &declared(&program)).map_err(|err| Error::asset(fragment, err))?;
+    Ok(program)
+}
+
+// the name & type of every uniform a program reads, less those GL sets itself
+fn declared(program: &Program) -> Vec<(&str, UniformType)> {
+    program.uniforms()
+           .filter(|&(name, _)| !name.starts_with("gl_"))
+           .map(|(name, uniform)| (name.as_str(), uniform.ty))
+           .collect()
+}
+
+// makes sure a program reads exactly the uniforms in its block, & `tex`
+fn check_uniforms(params: &Params, declared: &[(&str, UniformType)]) -> Result<()> {
+    for &(name, _) in declared {
+        if name != "tex" && params.get(name).is_none() {
+            return Err(Error::Uniforms(format!("uniform `{}` is not in the shader's params", name)));
+        }
+    }
+
+    for &(name, value) in params.values.iter().filter_map(|slot| slot.as_ref()) {
+        match declared.iter().find(|&&(key, _)| key == name) {
+            Some(&(_, ty)) if name != "tex" && ty == value.ty() => {},
+            Some(&(_, ty)) => return Err(Error::Uniforms(format!("uniform `{}` is a {:?}, not a {:?}", name, ty, value.ty()))),
+            None => return Err(Error::Uniforms(format!("uniform `{}` is not read by the shader", name))),
+        }
+    }
+
+    Ok(())
+}
+
+fn read_source(path: &str) -> Result<String> {
+    use std::fs::File;
+    use std::io::Read;
+
+    let mut source = String::new();
+    File::open(path)?.read_to_string(&mut source)?;
+    Ok(source)
+}
+
+#[cfg(test)]
+mod tests {
+    use glium::uniforms::UniformType;
+    use super::{check_uniforms, Params, Uniform, MAX_PARAMS};
+
+    static NAMES: [&'static str; MAX_PARAMS + 1] = ["a", "b", "c", "d", "e"];
+
+    #[test]
+    fn replaces_params_when_full() {
+        let params = NAMES[..MAX_PARAMS].iter().fold(Params::new(), |params, &name| params.set(name, Uniform::Float(1.0)));
+        assert_eq!(params.get("d"), Some(Uniform::Float(1.0)));
+
+        let params = params.set("a", Uniform::Float(2.0));
+        assert_eq!(params.get("a"), Some(Uniform::Float(2.0)));
+    }
+
+    #[test]
+    #[cfg_attr(debug_assertions, should_panic(expected = "too many uniforms"))]
+    fn overflowing_params_is_a_bug() {
+        let params = NAMES.iter().fold(Params::new(), |params, &name| params.set(name, Uniform::Float(1.0)));
+        assert_eq!(params.get("e"), None);
+    }
+
+    #[test]
+    fn binds_params_which_fit_the_block() {
+        let block  = Params::new().set("glow", Uniform::Float(1.0))
+                                  .set("tint", Uniform::Vec3([1.0; 3]));
+
+        let params = Params::new().set("glow", Uniform::Vec2([0.5; 2]))
+                                  .set("tint", Uniform::Vec3([0.5; 3]))
+                                  .set("typo", Uniform::Float(0.0));
+
+        let bound = block.bind(&params);
+        assert_eq!(bound.get("glow"), Some(Uniform::Float(1.0)));
+        assert_eq!(bound.get("tint"), Some(Uniform::Vec3([0.5; 3])));
+        assert_eq!(bound.get("typo"), None);
+    }
+
+    #[test]
+    fn checks_uniforms_against_the_program() {
+        let block = Params::new().set("glow", Uniform::Float(1.0));
+        let tex   = ("tex", UniformType::Sampler2d);
+
+        assert!(check_uniforms(&block, &[tex, ("glow", UniformType::Float)]).is_ok());
+        assert!(check_uniforms(&Params::new(), &[tex]).is_ok());
+
+        // wrong type, missing from the block, & not read by the program
+        assert!(check_uniforms(&block, &[tex, ("glow", UniformType::FloatVec2)]).is_err());
+        assert!(check_uniforms(&Params::new(), &[tex, ("glow", UniformType::Float)]).is_err());
+        assert!(check_uniforms(&block, &[tex]).is_err());
+
+        // `tex` belongs to the renderer
+        let block = Params::new().set("tex", Uniform::Float(1.0));
+        assert!(check_uniforms(&block, &[tex]).is_err());
+    }
+}
@@ -0,0 +1,213 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+
+use error::{Error, Result};
+use render::{TextureHandle, TextureStore, UvRect};
+
+/// A texture divided into numbered frames, e.g: the steps of an animation.
+///
+/// Frames are stored as fractions of the texture, so a sheet can be built
+/// w/o knowing how large the texture is or where it was packed.
+///
+/// Sheets are either a grid of equally sized cells (`SpriteSheet::grid()`)
+/// or are described by a manifest on disk (`SpriteSheet::load()`):
+///
+/// ```text
+/// image = assets/sprites/loader/flames.tga
+/// size  = 64 80        # width & height of the image, in pixels
+/// grid  = 16 16        # adds every 16x16 cell as a frame
+/// frame = 0 0 32 16    # adds a single frame: x y width height
+/// ```
+///
+/// Pixel coordinates start at the bottom-left of the image, like UVs,
+/// & every frame must lie within `size`. Frames are numbered in the order they are added, grid cells are added
+/// left to right, starting w/ the bottom row.
+pub struct SpriteSheet {
+    texture: TextureHandle,
+    frames:  Vec<UvRect>,
+}
+
+impl SpriteSheet {
+    /// A sheet w/o any frames.
+    pub fn new(texture: TextureHandle) -> Self {
+        SpriteSheet { texture: texture, frames: vec![] }
+    }
+
+    /// A sheet w/ `cols` x `rows` equally sized frames.
+    pub fn grid(texture: TextureHandle, cols: u32, rows: u32) -> Self {
+        let mut sheet = SpriteSheet::new(texture);
+        sheet.add_grid(cols, rows);
+        sheet
+    }
+
+    /// Reads a sheet from the manifest at `path`, the image it describes is
+    /// loaded into `store` in the background. See `SpriteSheet` for the
+    /// file format.
+    pub fn load<T: TextureStore>(store: &mut T, path: &str) -> Result<Self> {
+        read_manifest(store, path).map_err(|err| Error::asset(path, err))
+    }
+
+    /// Adds a frame covering `uv`, given as fractions of the whole texture.
+    pub fn add_frame(&mut self, uv: UvRect) { self.frames.push(uv); }
+
+    /// Adds `cols` x `rows` equally sized frames covering the whole texture.
+    pub fn add_grid(&mut self, cols: u32, rows: u32) {
+        let (w, h) = (1.0 / cols as f32, 1.0 / rows as f32);
+        for row in 0..rows {
+            for col in 0..cols {
+                self.frames.push(UvRect::whole().sub(col as f32 * w, row as f32 * h, w, h));
+            }
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn len(&self) -> usize { self.frames.len() }
+
+    /// A handle to draw frame `idx` of this sheet, or the whole texture
+    /// if the sheet has no such frame.
+    pub fn frame(&self, idx: usize) -> TextureHandle {
+        match self.frames.get(idx) {
+            Some(&uv) => TextureHandle { id: self.texture.id, uv: self.texture.uv.map(uv) },
+            None => self.texture,
+        }
+    }
+}
+
+enum Entry {
+    Image(String),
+    Size(u32, u32),
+    Grid(u32, u32),
+    Frame(u32, u32, u32, u32),
+}
+
+// builds a sheet from its manifest, which needs both `image` & `size`
+// before any frames can be added.
+fn read_manifest<T: TextureStore>(store: &mut T, path: &str) -> Result<SpriteSheet> {
+    let file = File::open(path)?;
+    let mut image = None;
+    let mut sheet: Option<(SpriteSheet, u32, u32)> = None;
+
+    for (line_no, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        let line = line.split('#').next().unwrap_or("").trim();
+        if line.is_empty() { continue }
+
+        let bad_entry = || Error::Parse(format!("line {}: bad sheet entry `{}`", line_no + 1, line));
+
+        match parse_line(line).ok_or_else(&bad_entry)? {
+            Entry::Image(name) if sheet.is_none() => image = Some(name),
+
+            Entry::Size(w, h) if sheet.is_none() => {
+                let texture = store.load_image_async(image.as_ref().ok_or_else(&bad_entry)?);
+                sheet = Some((SpriteSheet::new(texture), w, h));
+            },
+
+            Entry::Grid(cell_w, cell_h) => {
+                let (ref mut sheet, w, h) = *sheet.as_mut().ok_or_else(&bad_entry)?;
+                sheet.add_grid(w / cell_w, h / cell_h);
+            },
+
+            Entry::Frame(x, y, frame_w, frame_h) => {
+                let (ref mut sheet, w, h) = *sheet.as_mut().ok_or_else(&bad_entry)?;
+
+                // frames must lie within the sheet
+                let right = x.checked_add(frame_w).ok_or_else(&bad_entry)?;
+                let top   = y.checked_add(frame_h).ok_or_else(&bad_entry)?;
+                if right > w || top > h { return Err(bad_entry()) }
+
+                let (w, h) = (w as f32, h as f32);
+                sheet.add_frame(UvRect {
+                    u1: x as f32 / w,        v1: y as f32 / h,
+                    u2: right as f32 / w,    v2: top as f32 / h,
+                });
+            },
+
+            _ => return Err(bad_entry()),
+        }
+    }
+
+    match sheet {
+        Some((sheet, _, _)) => Ok(sheet),
+        None => Err(Error::Parse("sheet is missing its image or size".to_string())),
+    }
+}
+
+fn parse_line(line: &str) -> Option<Entry> {
+    let mut halves = line.splitn(2, '=');
+    let key   = halves.next()?.trim();
+    let value = halves.next()?.trim();
+
+    match key {
+        "image" => Some(Entry::Image(value.to_string())),
+        "size"  => parse_numbers(value, 2).and_then(|n| if n[0] > 0 && n[1] > 0 { Some(Entry::Size(n[0], n[1])) } else { None }),
+        "grid"  => parse_numbers(value, 2).and_then(|n| if n[0] > 0 && n[1] > 0 { Some(Entry::Grid(n[0], n[1])) } else { None }),
+        "frame" => parse_numbers(value, 4).and_then(|n| if n[2] > 0 && n[3] > 0 { Some(Entry::Frame(n[0], n[1], n[2], n[3])) } else { None }),
+        _ => None,
+    }
+}
+
+// parses exactly `count` whitespace separated numbers
+fn parse_numbers(value: &str, count: usize) -> Option<Vec<u32>> {
+    let numbers = value.split_whitespace()
+                       .map(|n| n.parse().ok())
+                       .collect::<Option<Vec<u32>>>()?;
+
+    if numbers.len() == count { Some(numbers) } else { None }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs::File;
+    use std::io::Write;
+
+    use render::{NullTextures, UvRect};
+    use super::SpriteSheet;
+
+    fn load(name: &str, manifest: &str) -> Result<SpriteSheet, String> {
+        let path = env::temp_dir().join(name);
+        let path = path.to_str().unwrap();
+        File::create(path).unwrap().write_all(manifest.as_bytes()).unwrap();
+
+        SpriteSheet::load(&mut NullTextures::new(), path).map_err(|err| err.to_string())
+    }
+
+    #[test]
+    fn reads_grids_and_frames() {
+        let sheet = load("megumin-sheet-test.sheet", "
+            # a comment
+            image = flames.tga
+            size  = 64 32
+            grid  = 32 16      # four cells
+            frame = 16 8 32 16
+        ").unwrap();
+
+        assert_eq!(sheet.len(), 5);
+        assert_eq!(sheet.frame(1).uv, UvRect { u1: 0.5, v1: 0.0, u2: 1.0, v2: 0.5 });
+        assert_eq!(sheet.frame(2).uv, UvRect { u1: 0.0, v1: 0.5, u2: 0.5, v2: 1.0 });
+        assert_eq!(sheet.frame(4).uv, UvRect { u1: 0.25, v1: 0.25, u2: 0.75, v2: 0.75 });
+
+        // frames past the end show the whole texture
+        assert_eq!(sheet.frame(5).uv, UvRect::whole());
+    }
+
+    #[test]
+    fn rejects_bad_manifests() {
+        assert!(load("megumin-sheet-nosize.sheet", "image = a.tga\ngrid = 8 8").is_err());
+        assert!(load("megumin-sheet-order.sheet", "size = 8 8\nimage = a.tga").is_err());
+        assert!(load("megumin-sheet-empty-grid.sheet", "image = a.tga\nsize = 8 8\ngrid = 0 8").is_err());
+        assert!(load("megumin-sheet-typo.sheet", "image = a.tga\nsize = 8 8\nframes = 0 0 8 8").is_err());
+    }
+
+    #[test]
+    fn rejects_frames_outside_the_sheet() {
+        assert!(load("megumin-sheet-zero-size.sheet", "image = a.tga\nsize = 0 0\nframe = 0 0 1 1").is_err());
+        assert!(load("megumin-sheet-zero-frame.sheet", "image = a.tga\nsize = 8 8\nframe = 0 0 0 8").is_err());
+        assert!(load("megumin-sheet-past-edge.sheet", "image = a.tga\nsize = 8 8\nframe = 4 0 8 8").is_err());
+        assert!(load("megumin-sheet-overflow.sheet", "image = a.tga\nsize = 8 8\nframe = 4294967295 0 1 1").is_err());
+
+        // a frame may cover the whole sheet
+        let sheet = load("megumin-sheet-whole.sheet", "image = a.tga\nsize = 8 8\nframe = 0 0 8 8").unwrap();
+        assert_eq!(sheet.frame(0).uv, UvRect::whole());
+    }
+}
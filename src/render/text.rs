@@ -0,0 +1,275 @@
+use glium;
+use glium::Surface;
+use rusttype::{point, Font, FontCollection, PositionedGlyph, Scale, ScaledGlyph};
+use rusttype::gpu_cache::Cache;
+
+use error::{Error, Result};
+use render::{Params, Pass, Rect, RenderGroup, RenderJob, Tint, Transform, UvRect, View, WHITE};
+use render::shaders;
+use units::drawing::RGBA;
+
+// width & height of the glyph cache, in pixels
+pub static GLYPH_CACHE_SIZE: u32 = 512;
+
+// glyphs are re-rasterized once they are drawn this much larger or smaller
+// (as a fraction of their size), or this far off the pixel grid (in pixels.)
+static SCALE_TOLERANCE:    f32 = 0.1;
+static POSITION_TOLERANCE: f32 = 0.1;
+
+// text is drawn in front of the world unless asked otherwise
+static DEFAULT_TEXT_SIZE:  f32 = 16.0;
+static DEFAULT_TEXT_DEPTH: f32 = -0.9;
+
+static DEFAULT_FONT: &'static [u8] = include_bytes!("../../assets/fonts/DejaVuSansMono.ttf");
+
+/// How the lines of a `Text` are placed relative to its position.
+#[allow(dead_code)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Align { Left, Center, Right }
+
+/// A string drawn on screen, see `RenderJob::DrawText`.
+///
+/// Text is measured in pixels from the top-left corner of the window,
+/// like the mouse cursor. The position is the top of the first line, and
+/// its left edge, center, or right edge depending on the alignment.
+#[derive(Clone, Debug)]
+pub struct Text {
+    string:   String,
+    position: (f32, f32),
+    depth:    f32,
+    size:     f32,
+    color:    Tint,
+    align:    Align,
+    wrap:     Option<f32>,
+}
+
+impl Text {
+    pub fn from(string: &str, x: f32, y: f32) -> Text {
+        Text {
+            string:   string.to_string(),
+            position: (x, y),
+            depth:    DEFAULT_TEXT_DEPTH,
+            size:     DEFAULT_TEXT_SIZE,
+            color:    WHITE,
+            align:    Align::Left,
+            wrap:     None,
+        }
+    }
+
+    /// Sets the height of a line of text, in pixels.
+    pub fn size(mut self, size: f32) -> Self { self.size = size; self }
+
+    pub fn color(mut self, color: Tint) -> Self { self.color = color; self }
+
+    pub fn align(mut self, align: Align) -> Self { self.align = align; self }
+
+    #[allow(dead_code)]
+    pub fn depth(mut self, depth: f32) -> Self { self.depth = depth; self }
+
+    /// Breaks lines which would be wider than `width` pixels, between
+    /// words where possible.
+    pub fn wrap(mut self, width: f32) -> Self { self.wrap = Some(width); self }
+}
+
+/// Rasterizes glyphs on demand into a region of an atlas page.
+///
+/// Each frame the glyphs of every piece of text are laid out & queued,
+/// then any which are missing are uploaded at once, before the first of
+/// them is drawn. Glyphs which have not been used recently are evicted
+/// to make room for new ones.
+pub struct GlyphCache {
+    font:  Font<'static>,
+    cache: Cache,
+
+    page:      usize,
+    origin:    (u32, u32),
+    page_size: (u32, u32),
+}
+
+impl GlyphCache {
+    /// Builds a cache which occupies the `GLYPH_CACHE_SIZE` square region
+    /// of `page` starting at `origin`.
+    pub fn new(page: usize, origin: (u32, u32), page_size: (u32, u32)) -> Result<Self> {
+        let font = FontCollection::from_bytes(DEFAULT_FONT).into_font()
+                                  .ok_or_else(|| Error::Parse("could not parse default font".to_string()))?;
+
+        Ok(GlyphCache {
+            font:  font,
+            cache: Cache::new(GLYPH_CACHE_SIZE, GLYPH_CACHE_SIZE, SCALE_TOLERANCE, POSITION_TOLERANCE),
+
+            page:      page,
+            origin:    origin,
+            page_size: page_size,
+        })
+    }
+
+    /// The atlas page the cache lives on.
+    pub fn page(&self) -> usize { self.page }
+
+    /// Positions the glyphs of `text` & queues them to be cached.
+    pub fn layout(&mut self, text: &Text) -> Vec<PositionedGlyph<'static>> {
+        let glyphs = layout(&self.font, text);
+        for glyph in &glyphs { self.cache.queue_glyph(0, glyph.clone()); }
+        glyphs
+    }
+
+    /// Rasterizes every queued glyph which isn't cached yet, passing each
+    /// one to `upload` w/ its location on the page & its rows of pixels.
+    pub fn cache_queued<F>(&mut self, mut upload: F) where F: FnMut(glium::Rect, Vec<Vec<RGBA>>) {
+        let (ox, oy) = self.origin;
+
+        let cached = self.cache.cache_queued(|rect, coverage| {
+            let (w, h) = (rect.width() as usize, rect.height() as usize);
+            let pixels = coverage.chunks(w.max(1)).take(h).map(|row| {
+                row.iter().map(|&alpha| (255u8, 255u8, 255u8, alpha)).collect()
+            }).collect();
+
+            let region = glium::Rect {
+                left:   ox + rect.min.x,
+                bottom: oy + rect.min.y,
+                width:  rect.width(),
+                height: rect.height(),
+            };
+
+            upload(region, pixels);
+        });
+
+        if let Err(err) = cached { println!("could not cache glyphs: {:?}", err); }
+    }
+
+    /// The part of the page & screen-space rect (in pixels, y pointing down)
+    /// for a glyph which has been cached, or `None` for blank glyphs like spaces.
+    pub fn rect_for(&self, glyph: &PositionedGlyph) -> Option<(UvRect, Rect)> {
+        let (uv, screen) = match self.cache.rect_for(0, glyph) {
+            Ok(Some(rects)) => rects,
+            _ => return None,
+        };
+
+        // cache rows are stored top to bottom, so the top of a glyph is
+        // at the smaller v coordinate.
+        let (ox, oy) = (self.origin.0 as f32, self.origin.1 as f32);
+        let (pw, ph) = (self.page_size.0 as f32, self.page_size.1 as f32);
+        let size = GLYPH_CACHE_SIZE as f32;
+
+        let uv = UvRect {
+            u1: (ox + uv.min.x * size) / pw, v1: (oy + uv.max.y * size) / ph,
+            u2: (ox + uv.max.x * size) / pw, v2: (oy + uv.min.y * size) / ph,
+        };
+
+        let screen = Rect {
+            x: screen.min.x as f32, y: screen.max.y as f32, z: 0.0,
+            w: (screen.max.x - screen.min.x) as f32,
+            h: (screen.max.y - screen.min.y) as f32,
+        };
+
+        Some((uv, screen))
+    }
+}
+
+impl<'scn> RenderGroup<'scn> {
+    // lays out every string in `draw_list` & caches any glyphs they need,
+    // w/o a glyph cache there is nothing to lay out so text is skipped.
+    pub(super) fn layout_text(&mut self, draw_list: &[RenderJob]) -> Vec<Vec<PositionedGlyph<'static>>> {
+        let glyphs = match self.glyphs.as_mut() {
+            Some(glyphs) => glyphs,
+            None => return vec![],
+        };
+
+        let layouts = draw_list.iter().filter_map(|job| match *job {
+            RenderJob::DrawText(ref text) => Some(glyphs.layout(text)),
+            _ => None,
+        }).collect();
+
+        let page = &self.pages[glyphs.page()];
+        glyphs.cache_queued(|region, pixels| page.write(region, pixels));
+        layouts
+    }
+
+    // queues a quad for each (cached) glyph of `text`
+    pub(super) fn push_text<S: Surface>(&mut self, frame: &mut S, text: &Text, glyphs: &[PositionedGlyph<'static>]) -> Result<()> {
+        let (w, h) = frame.get_dimensions();
+        let (w, h) = (w as f32, h as f32);
+        let screen = View::screen();
+        let page   = self.glyphs.as_ref().map_or(0, |cache| cache.page());
+        let pass   = Pass { page: page, shader: shaders::TINTED, params: Params::new() };
+
+        for glyph in glyphs {
+            let rect = self.glyphs.as_ref().and_then(|cache| cache.rect_for(glyph));
+            let (uv, px) = match rect { Some(rect) => rect, None => continue };
+
+            // pixels (from the top-left) to screen space (from the bottom-left)
+            let dim = Rect { x: px.x / w, y: 1.0 - (px.y / h), z: text.depth, w: px.w / w, h: px.h / h };
+            self.push_rect(frame, pass, uv, text.color, dim, &Transform::identity(), &screen)?;
+        }
+
+        Ok(())
+    }
+}
+
+// a line of glyphs, each w/ its offset from the start of the line
+struct Line { glyphs: Vec<(ScaledGlyph<'static>, f32)>, width: f32 }
+
+// lays out each line of `text`, wrapping & aligning them as requested
+fn layout(font: &Font<'static>, text: &Text) -> Vec<PositionedGlyph<'static>> {
+    let scale   = Scale::uniform(text.size);
+    let metrics = font.v_metrics(scale);
+    let advance_height = metrics.ascent - metrics.descent + metrics.line_gap;
+
+    let mut lines = vec![];
+    for paragraph in text.string.lines() {
+        let mut line   = Line { glyphs: vec![], width: 0.0 };
+        let mut caret  = 0.0;
+        let mut last   = None;
+        let mut breaks = None; // index of the glyph after the most recent space
+
+        for ch in paragraph.chars() {
+            if ch.is_control() { continue }
+            let glyph = match font.glyph(ch) { Some(glyph) => glyph.scaled(scale), None => continue };
+
+            if let Some(id) = last.take() { caret += font.pair_kerning(scale, id, glyph.id()); }
+            last = Some(glyph.id());
+
+            let advance = glyph.h_metrics().advance_width;
+            let overflow = text.wrap.map_or(false, |width| caret + advance > width);
+
+            // move the word being typed (or just this glyph, if the word
+            // fills the whole line) onto a line of its own
+            if overflow && ch != ' ' && !line.glyphs.is_empty() {
+                let split = breaks.take().unwrap_or(line.glyphs.len());
+                let rest  = line.glyphs.split_off(split);
+                let shift = rest.first().map_or(caret, |&(_, x)| x);
+
+                // a space left at the end of the line doesn't count toward its width
+                line.width = if rest.is_empty() { caret } else { line.glyphs.last().map_or(0.0, |&(_, x)| x) };
+                lines.push(line);
+
+                line   = Line { glyphs: rest.into_iter().map(|(g, x)| (g, x - shift)).collect(), width: 0.0 };
+                caret -= shift;
+            }
+
+            line.glyphs.push((glyph, caret));
+            caret += advance;
+            if ch == ' ' { breaks = Some(line.glyphs.len()); }
+        }
+
+        line.width = caret;
+        lines.push(line);
+    }
+
+    let (x, y) = text.position;
+    let mut glyphs = vec![];
+    for (row, line) in lines.into_iter().enumerate() {
+        let left = match text.align {
+            Align::Left   => x,
+            Align::Center => x - (line.width / 2.0),
+            Align::Right  => x - line.width,
+        };
+
+        let baseline = y + metrics.ascent + (row as f32 * advance_height);
+        for (glyph, offset) in line.glyphs {
+            glyphs.push(glyph.positioned(point(left + offset, baseline)));
+        }
+    }
+
+    glyphs
+}
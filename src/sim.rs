@@ -0,0 +1,84 @@
+use std::time::Duration;
+
+use camera::Camera;
+use entities::{Sprite, World};
+use input::Input;
+use render::{RenderJob, Text, TextureStore, View};
+
+// each notch of the mouse wheel zooms in (or out) by this factor
+static WHEEL_ZOOM_STEP: f32 = 1.1;
+
+// distance (in pixels) from the HUD to the corner of the window
+static HUD_MARGIN: f32 = 8.0;
+
+/// Simulation owns every entity in the game and knows how to advance
+/// them by a single step. It never touches the display: entities are
+/// built against a `TextureStore` and only emit `RenderJob`s when asked,
+/// so the same simulation can run in a window or headless.
+pub struct Simulation {
+    tick:   u64,
+    camera: Camera,
+    player: Sprite,
+    world:  World,
+}
+
+impl Simulation {
+    pub fn new<T: TextureStore>(textures: &mut T, seed: [u32; 4]) -> Self {
+        let player = Sprite::new(textures);
+
+        Simulation {
+            tick:   0,
+            camera: Camera::new(player.position()),
+            world:  World::new(seed),
+            player: player,
+        }
+    }
+
+    /// Number of steps simulated so far.
+    pub fn tick(&self) -> u64 { self.tick }
+
+    /// The player's ship.
+    #[cfg(test)]
+    pub fn player(&self) -> &Sprite { &self.player }
+
+    /// Advances every entity by `dt` using the currently buffered input.
+    pub fn step(&mut self, controller: &Input, dt: Duration) {
+        self.player.set_bounds(self.camera.visible_bounds());
+        self.player.update(controller, dt);
+
+        // the camera trails the player, zooming w/ the mouse wheel
+        let (_, wheel_dy) = controller.get_wheel();
+        if wheel_dy != 0.0 { self.camera.zoom_by(WHEEL_ZOOM_STEP.powf(wheel_dy)); }
+        self.camera.set_viewport(controller.get_window_size());
+        self.camera.follow(self.player.position());
+        self.camera.update(dt);
+
+        self.world.update(self.camera.visible_bounds());
+        self.tick += 1;
+    }
+
+    /// Queues up the entities blended `alpha` of the way into the current step.
+    pub fn draw(&self, jobs: &mut Vec<RenderJob>, alpha: f32) {
+        self.player.draw(jobs, alpha);
+        self.world.draw(jobs);
+
+        let speed = self.player.velocity().len() * 1000.0;
+        let hud   = format!("speed {:.2} u/s\nzoom  {:.2}x", speed, self.camera.zoom());
+        jobs.push(RenderJob::DrawText(Text::from(&hud, HUD_MARGIN, HUD_MARGIN)));
+    }
+
+    /// The camera's view of the world onto a `window` sized window,
+    /// blended like `draw()`.
+    pub fn view(&self, alpha: f32, window: (u32, u32)) -> View { self.camera.view(alpha, window) }
+
+    /// Prints a summary of the simulation state to stdout.
+    pub fn dump(&self) {
+        println!("tick:      {}", self.tick);
+        println!("position:  {:?}", self.player.position());
+        println!("velocity:  {:?}", self.player.velocity());
+        println!("rotation:  {}", self.player.rotation());
+        println!("particles: {}", self.player.particle_count());
+        println!("stars:     {}", self.world.star_count());
+        println!("camera:    {:?} @ {}x", self.camera.position(), self.camera.zoom());
+    }
+}
@@ -8,7 +8,8 @@ pub struct V2 {
 
 impl V2 {
     pub fn at(x: f32, y: f32) -> Self { V2 { x: x, y: y } }
-    
+
+    #[allow(dead_code)]
     pub fn dot(self, rhs: V2) -> f32 {
         (self.x * rhs.x) + (self.y * rhs.y)
     }
@@ -47,6 +48,11 @@ impl V2 {
     pub fn theta(self) -> f32 {
         f32::atan2(self.y, self.x)
     }
+
+    /// Linearly interpolates from `self` (t = 0.0) to `rhs` (t = 1.0)
+    pub fn lerp(self, rhs: V2, t: f32) -> V2 {
+        self + ((rhs - self) * t)
+    }
 }
 
 impl Add for V2 {